@@ -0,0 +1,179 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitStr};
+
+#[derive(Default)]
+struct StructOptions {
+    prefix: Option<String>,
+    sep: Option<String>,
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    before: Option<String>,
+}
+
+fn parse_struct_options(attrs: &[Attribute]) -> syn::Result<StructOptions> {
+    let mut options = StructOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("prefix") {
+                options.prefix = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("sep") {
+                options.sep = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `prefix` or `sep`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn parse_field_options(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("before") {
+                options.before = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `before`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+/// Derives a parser for one line shaped like the struct: an optional `prefix` literal, then the
+/// fields in order. Consecutive fields are separated by whitespace, by the struct's `sep`
+/// literal, or by the field's own `before` literal.
+///
+/// ```ignore
+/// #[derive(AocParse)]
+/// #[aoc(prefix = "Card", sep = "|")]
+/// struct Card {
+///     id: u32,
+///     #[aoc(before = ":")]
+///     winning_numbers: Vec<u32>,
+///     scratch_numbers: Vec<u32>,
+/// }
+/// ```
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let options = parse_struct_options(&input.attrs)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "AocParse needs named fields")),
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "AocParse can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut steps = Vec::new();
+    let mut names = Vec::new();
+
+    if let Some(prefix) = &options.prefix {
+        let expected = format!("`{}`", prefix);
+        steps.push(quote! {
+            let input = support::literal(input, #prefix)
+                .map_err(|at| error("", #expected.to_string(), at))?;
+        });
+    }
+
+    for (index, field) in fields.iter().enumerate() {
+        let ident = field.ident.as_ref().unwrap();
+        let field_name = ident.to_string();
+        let ty = &field.ty;
+        let field_options = parse_field_options(&field.attrs)?;
+
+        let separator = match (field_options.before, index) {
+            (Some(before), _) => Some(before),
+            (None, 0) => None,
+            (None, _) => options.sep.clone(),
+        };
+        if let Some(separator) = separator {
+            let expected = format!("`{}`", separator);
+            steps.push(quote! {
+                let input = support::literal(input, #separator)
+                    .map_err(|at| error(#field_name, #expected.to_string(), at))?;
+            });
+        }
+
+        let expected = quote!(#ty).to_string().replace(' ', "");
+        steps.push(quote! {
+            let (input, #ident) = support::field::<#ty>(input)
+                .map_err(|at| error(#field_name, #expected.to_string(), at))?;
+        });
+        names.push(ident);
+    }
+
+    Ok(quote! {
+        impl #name {
+            /// Parses the fields from the start of `input`, returning the unparsed rest.
+            pub fn parse_fields(
+                input: &str,
+            ) -> ::std::result::Result<(&str, Self), ::advent_of_code::parsing::FieldError> {
+                use ::advent_of_code::parsing::support;
+
+                let original = input;
+                let error = |field: &'static str, expected: ::std::string::String, at: &str| {
+                    ::advent_of_code::parsing::FieldError {
+                        type_name: #type_name,
+                        field,
+                        expected,
+                        offset: original.len() - at.len(),
+                    }
+                };
+
+                #(#steps)*
+
+                Ok((input, Self { #(#names),* }))
+            }
+        }
+
+        impl ::advent_of_code::parsing::FromInput for #name {
+            fn from_input(
+                input: &str,
+            ) -> ::advent_of_code::parsing::support::IResult<&str, Self> {
+                Self::parse_fields(input)
+                    .map_err(|err| ::advent_of_code::parsing::support::nom_error(input, err.offset))
+            }
+        }
+
+        impl ::std::str::FromStr for #name {
+            type Err = ::advent_of_code::parsing::FieldError;
+
+            fn from_str(input: &str) -> ::std::result::Result<Self, Self::Err> {
+                let (rest, parsed) = Self::parse_fields(input)?;
+                if rest.trim().is_empty() {
+                    Ok(parsed)
+                } else {
+                    Err(::advent_of_code::parsing::FieldError {
+                        type_name: #type_name,
+                        field: "",
+                        expected: "end of input".to_string(),
+                        offset: input.len() - rest.len(),
+                    })
+                }
+            }
+        }
+    })
+}
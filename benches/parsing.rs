@@ -0,0 +1,393 @@
+//! Compares the parsers the puzzles used before and after the move to `byte_parsing`, on
+//! generated puzzle shaped input. Run with `cargo bench --bench parsing`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+// the binaries are included whole, so the benchmarks run the parsers the puzzles really use
+#[allow(dead_code, unused_imports)]
+mod day_02 {
+    pub fn part_1(input: &str) -> u32 {
+        solution_1(input, "bench").unwrap()
+    }
+
+    include!("../src/bin/year_2023_day_02.rs");
+}
+
+#[allow(dead_code, unused_imports)]
+mod day_03 {
+    pub fn part_1(input: &str) -> u32 {
+        solution_1(input).unwrap()
+    }
+
+    include!("../src/bin/year_2023_day_03.rs");
+}
+
+#[allow(dead_code, unused_imports)]
+mod day_04 {
+    // the derived parser is the fallback that explains malformed cards
+    pub fn score_derive(input: &str) -> u32 {
+        parse_records("bench", input)
+            .unwrap()
+            .iter()
+            .map(Card::score)
+            .sum()
+    }
+
+    pub fn score_bytes(input: &str) -> u32 {
+        solution_1(input, "bench").unwrap()
+    }
+
+    include!("../src/bin/year_2023_day_04.rs");
+}
+
+// the day 3 part 1 as it was before the port
+#[allow(clippy::all)]
+mod day_03_nom {
+    use advent_of_code::grid::BitTable;
+    use advent_of_code::parsing::unsigned;
+    use nom::IResult;
+
+    fn parse_number(input: &str) -> IResult<&str, u32> {
+        unsigned(input)
+    }
+
+    pub fn solution_1(input: &str) -> u32 {
+        let mut solution = 0;
+
+        let line_length = input.lines().nth(0).unwrap().len();
+        let mut bit_map: BitTable = BitTable::new(line_length as u32, input.lines().count() as u32);
+
+        for (y, line) in input.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if !c.is_numeric() && c != '.' {
+                    bit_map.set_around(x as u32, y as u32, true);
+                }
+            }
+        }
+
+        let mut input_result = &input[..];
+
+        let mut index = 0;
+        while input_result.len() > 0 {
+            let c = input_result.chars().nth(0).unwrap();
+            match c {
+                c if c.is_numeric() => {
+                    let x = index % line_length;
+                    let y = index / line_length;
+
+                    let number: u32;
+                    let digit_count = input_result.chars().take_while(|c| c.is_digit(10)).count();
+
+                    (input_result, number) = parse_number(input_result).unwrap();
+
+                    let is_included = (0..digit_count).fold(false, |acc, next| {
+                        acc || bit_map.is_set(x as u32 + next as u32, y as u32)
+                    });
+
+                    if is_included {
+                        solution += number;
+                    }
+
+                    index += digit_count;
+                }
+                '\n' => {
+                    input_result = &input_result[1..];
+                }
+                _ => {
+                    input_result = &input_result[1..];
+                    index += 1;
+                }
+            }
+        }
+
+        solution
+    }
+}
+
+// the nom based day 4 part 1 as it was before the port
+#[allow(dead_code, clippy::needless_return, clippy::collapsible_else_if)]
+mod day_04_nom {
+    use advent_of_code::parsing::{labelled, number_list, parse_lines, unsigned};
+    use nom::{
+        character::complete::{char, space0},
+        error::{context, VerboseError},
+        sequence::{preceded, terminated, tuple},
+        IResult,
+    };
+
+    struct Card {
+        id: u32,
+        winning_numbers: Vec<u32>,
+        scratch_numbers: Vec<u32>,
+    }
+
+    impl Card {
+        fn win_amount(&self) -> u32 {
+            let mut bits_part_1: u64 = 0;
+            let mut bits_part_2: u64 = 0;
+
+            for number in &self.winning_numbers {
+                if *number > 50 {
+                    bits_part_2 |= 1 << (*number - 50);
+                } else {
+                    bits_part_1 |= 1 << *number;
+                }
+            }
+
+            let mut amount = 0;
+            for number in &self.scratch_numbers {
+                if *number > 50 {
+                    if bits_part_2 & (1 << (*number - 50)) != 0 {
+                        amount += 1;
+                    }
+                } else {
+                    if bits_part_1 & (1 << *number) != 0 {
+                        amount += 1;
+                    }
+                }
+            }
+
+            return amount;
+        }
+
+        fn score(&self) -> u32 {
+            let amount = self.win_amount();
+            match amount {
+                0 => 0,
+                x => 2u32.pow(x - 1),
+            }
+        }
+    }
+
+    fn parse_numbers(input: &str) -> IResult<&str, Vec<u32>, VerboseError<&str>> {
+        context("a list of numbers", number_list(unsigned))(input)
+    }
+
+    fn parse_card(input: &str) -> IResult<&str, Card, VerboseError<&str>> {
+        let (input, (id, winning_numbers, _, scratch_numbers)) = tuple((
+            context("`Card <number>:`", labelled("Card")),
+            parse_numbers,
+            preceded(space0, char('|')),
+            terminated(parse_numbers, space0),
+        ))(input)?;
+
+        Ok((
+            input,
+            Card {
+                id,
+                winning_numbers,
+                scratch_numbers,
+            },
+        ))
+    }
+
+    pub fn part_1(input: &str) -> u32 {
+        let cards = parse_lines("bench", input, parse_card).unwrap();
+
+        cards.iter().map(Card::score).sum()
+    }
+}
+
+// the nom based day 2 part 1 as it was before the port
+mod day_02_nom {
+    use advent_of_code::parsing::{comma_list, labelled, semicolon_list, unsigned};
+    use nom::{
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::space1,
+        combinator::value,
+        sequence::{pair, separated_pair},
+        IResult, Parser,
+    };
+
+    #[derive(Clone, Copy)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    fn parse_color(input: &str) -> IResult<&str, Color> {
+        alt((
+            value(Color::Red, tag("red")),
+            value(Color::Green, tag("green")),
+            value(Color::Blue, tag("blue")),
+        ))(input)
+    }
+
+    fn parse_color_count(input: &str) -> IResult<&str, (Color, u32)> {
+        let (input, (count, color)) = separated_pair(unsigned, space1, parse_color)(input)?;
+
+        Ok((input, (color, count)))
+    }
+
+    fn is_color_possible(color: Color, count: u32) -> bool {
+        match color {
+            Color::Red => count <= 12,
+            Color::Green => count <= 13,
+            Color::Blue => count <= 14,
+        }
+    }
+
+    fn parse_is_color_possible(input: &str) -> IResult<&str, bool> {
+        let (input, (color, count)) = parse_color_count(input)?;
+        Ok((input, is_color_possible(color, count)))
+    }
+
+    fn parse_is_round_possible(input: &str) -> IResult<&str, bool> {
+        comma_list(parse_is_color_possible)
+            .map(|colors| colors.iter().all(|possible| *possible))
+            .parse(input)
+    }
+
+    fn parse_are_rounds_possible(input: &str) -> IResult<&str, bool> {
+        semicolon_list(parse_is_round_possible)
+            .map(|rounds| rounds.iter().all(|possible| *possible))
+            .parse(input)
+    }
+
+    fn parse_game_id(input: &str) -> IResult<&str, u32> {
+        labelled("Game")(input)
+    }
+
+    fn parse_is_game_possible(input: &str) -> IResult<&str, (u32, bool)> {
+        pair(parse_game_id, parse_are_rounds_possible)(input)
+    }
+
+    pub fn part_1(input: &str) -> u32 {
+        let mut solution: u32 = 0;
+
+        for line in input.lines() {
+            let (_, (game_id, possible)) = parse_is_game_possible(line).unwrap();
+
+            if possible {
+                solution += game_id;
+            }
+        }
+
+        solution
+    }
+}
+
+// a xorshift so the inputs are the same on every run
+fn numbers(count: usize, max: u32) -> Vec<u32> {
+    let mut state: u32 = 2463534242;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % max + 1
+        })
+        .collect()
+}
+
+fn cards_input() -> String {
+    let numbers = numbers(35 * 200, 99);
+    numbers
+        .chunks(35)
+        .enumerate()
+        .map(|(index, numbers)| {
+            let list = |numbers: &[u32]| {
+                numbers
+                    .iter()
+                    .map(|number| format!("{:>2}", number))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            format!(
+                "Card {:>3}: {} | {}",
+                index + 1,
+                list(&numbers[..10]),
+                list(&numbers[10..])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn games_input() -> String {
+    let numbers = numbers(12 * 100, 20);
+    let colors = ["red", "green", "blue"];
+    numbers
+        .chunks(12)
+        .enumerate()
+        .map(|(index, numbers)| {
+            let rounds = numbers
+                .chunks(3)
+                .map(|round| {
+                    round
+                        .iter()
+                        .zip(colors)
+                        .map(|(count, color)| format!("{} {}", count, color))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!("Game {}: {}", index + 1, rounds)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn schematic_input() -> String {
+    // mostly empty cells like the puzzle input, so no run of digits overflows a u32
+    numbers(140 * 140, 32)
+        .chunks(140)
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    1..=4 => char::from_digit(*cell, 10).unwrap(),
+                    5 => '*',
+                    6 => '#',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn bench_cards(c: &mut Criterion) {
+    let input = cards_input();
+    assert_eq!(day_04_nom::part_1(&input), day_04::score_bytes(&input));
+    assert_eq!(day_04::score_derive(&input), day_04::score_bytes(&input));
+
+    let mut group = c.benchmark_group("day 4 card scores");
+    group.bench_function("nom", |b| b.iter(|| day_04_nom::part_1(black_box(&input))));
+    group.bench_function("derive", |b| {
+        b.iter(|| day_04::score_derive(black_box(&input)))
+    });
+    group.bench_function("bytes", |b| {
+        b.iter(|| day_04::score_bytes(black_box(&input)))
+    });
+    group.finish();
+}
+
+fn bench_games(c: &mut Criterion) {
+    let input = games_input();
+    assert_eq!(day_02_nom::part_1(&input), day_02::part_1(&input));
+
+    let mut group = c.benchmark_group("day 2 possible games");
+    group.bench_function("nom", |b| b.iter(|| day_02_nom::part_1(black_box(&input))));
+    group.bench_function("bytes", |b| b.iter(|| day_02::part_1(black_box(&input))));
+    group.finish();
+}
+
+fn bench_schematic(c: &mut Criterion) {
+    let input = schematic_input();
+    assert_eq!(day_03_nom::solution_1(&input), day_03::part_1(&input));
+
+    let mut group = c.benchmark_group("day 3 part numbers");
+    group.bench_function("nom", |b| {
+        b.iter(|| day_03_nom::solution_1(black_box(&input)))
+    });
+    group.bench_function("bytes", |b| b.iter(|| day_03::part_1(black_box(&input))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_cards, bench_games, bench_schematic);
+criterion_main!(benches);
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+
+/// An occurrence of pattern number `pattern` at `start..end` of the haystack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Aho-Corasick automaton finding all occurrences of a set of byte patterns in one pass,
+/// overlapping ones included: `one` and `two` both match in `twone`.
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    // dense transitions, failure links already folded in
+    transitions: Vec<[u32; 256]>,
+    // patterns ending at each state, including those of its suffix states
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Pattern `i` of `patterns` is reported as `Match::pattern == i`, empty patterns never match.
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut transitions = vec![[0u32; 256]];
+        let mut outputs = vec![Vec::new()];
+        let mut lengths = Vec::new();
+
+        // 0 doubles as "no child yet" while building the trie, the root is never a child
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref();
+            lengths.push(pattern.len());
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut state = 0;
+            for &byte in pattern {
+                if transitions[state][byte as usize] == 0 {
+                    transitions.push([0; 256]);
+                    outputs.push(Vec::new());
+                    transitions[state][byte as usize] = (transitions.len() - 1) as u32;
+                }
+                state = transitions[state][byte as usize] as usize;
+            }
+            outputs[state].push(index);
+        }
+
+        let mut failure = vec![0usize; transitions.len()];
+        let mut queue = transitions[0]
+            .iter()
+            .filter(|&&child| child != 0)
+            .map(|&child| child as usize)
+            .collect::<VecDeque<_>>();
+
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[failure[state]].clone();
+            outputs[state].extend(inherited);
+            outputs[state].sort_unstable();
+
+            let fallbacks = transitions[failure[state]];
+            for (child, fallback) in transitions[state].iter_mut().zip(fallbacks) {
+                if *child == 0 {
+                    *child = fallback;
+                } else {
+                    failure[*child as usize] = fallback as usize;
+                    queue.push_back(*child as usize);
+                }
+            }
+        }
+
+        AhoCorasick {
+            transitions,
+            outputs,
+            lengths,
+        }
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Every match in order of its end, matches ending together in pattern order.
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a [u8]) -> FindOverlapping<'a> {
+        FindOverlapping {
+            automaton: self,
+            haystack,
+            position: 0,
+            state: 0,
+            pending: &[],
+        }
+    }
+
+    /// The match starting first, the longest one if several start there.
+    pub fn first_match(&self, haystack: &[u8]) -> Option<Match> {
+        let longest = self.lengths.iter().copied().max().unwrap_or(0);
+        let mut best: Option<Match> = None;
+        for found in self.find_overlapping(haystack) {
+            if let Some(best) = best {
+                // nothing ending from here on can start before `best`
+                if found.end > best.start + longest {
+                    break;
+                }
+            }
+            if best.is_none_or(|best| Self::earlier(&found, &best)) {
+                best = Some(found);
+            }
+        }
+        best
+    }
+
+    /// The match starting last, the longest one if several start there.
+    pub fn last_match(&self, haystack: &[u8]) -> Option<Match> {
+        self.find_overlapping(haystack)
+            .max_by_key(|found| (found.start, found.end))
+    }
+
+    fn earlier(a: &Match, b: &Match) -> bool {
+        (a.start, std::cmp::Reverse(a.end)) < (b.start, std::cmp::Reverse(b.end))
+    }
+}
+
+/// Iterator returned by [`AhoCorasick::find_overlapping`].
+pub struct FindOverlapping<'a> {
+    automaton: &'a AhoCorasick,
+    haystack: &'a [u8],
+    position: usize,
+    state: usize,
+    pending: &'a [usize],
+}
+
+impl Iterator for FindOverlapping<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        loop {
+            if let Some((&pattern, rest)) = self.pending.split_first() {
+                self.pending = rest;
+                return Some(Match {
+                    pattern,
+                    start: self.position - self.automaton.lengths[pattern],
+                    end: self.position,
+                });
+            }
+
+            let &byte = self.haystack.get(self.position)?;
+            self.state = self.automaton.transitions[self.state][byte as usize] as usize;
+            self.position += 1;
+            self.pending = &self.automaton.outputs[self.state];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn matches(automaton: &AhoCorasick, haystack: &str) -> Vec<(usize, usize)> {
+        automaton
+            .find_overlapping(haystack.as_bytes())
+            .map(|found| (found.pattern, found.start))
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_test() {
+        let automaton = AhoCorasick::new(["one", "two", "twone", "ne", ""]);
+
+        assert_eq!(
+            matches(&automaton, "xtwone"),
+            [(1, 1), (0, 3), (2, 1), (3, 4)]
+        );
+        assert_eq!(matches(&automaton, "tw"), []);
+    }
+
+    #[test]
+    fn first_last_test() {
+        let automaton = AhoCorasick::new(["one", "two", "eight", "ei", "1"]);
+
+        let first = automaton.first_match(b"xeightwo1").unwrap();
+        assert_eq!((first.pattern, first.start, first.end), (2, 1, 6));
+        let last = automaton.last_match(b"xeightwo1").unwrap();
+        assert_eq!((last.pattern, last.start), (4, 8));
+        assert_eq!(automaton.first_match(b"abc"), None);
+    }
+
+    proptest! {
+        #[test]
+        fn naive_search_test(
+            patterns in prop::collection::vec("[ab]{1,4}", 1..6),
+            haystack in "[abc]{0,30}",
+        ) {
+            let automaton = AhoCorasick::new(&patterns);
+
+            let mut expected = Vec::new();
+            for end in 1..=haystack.len() {
+                for (pattern, word) in patterns.iter().enumerate() {
+                    if haystack[..end].ends_with(word.as_str()) {
+                        expected.push(Match { pattern, start: end - word.len(), end });
+                    }
+                }
+            }
+            let found = automaton.find_overlapping(haystack.as_bytes()).collect::<Vec<_>>();
+            prop_assert_eq!(&found, &expected);
+
+            let first = expected.iter().min_by_key(|m| (m.start, std::cmp::Reverse(m.end)));
+            let first_found = automaton.first_match(haystack.as_bytes());
+            prop_assert_eq!(
+                first_found.map(|m| (m.start, m.end)),
+                first.map(|m| (m.start, m.end))
+            );
+            let last = expected.iter().max_by_key(|m| (m.start, m.end));
+            let last_found = automaton.last_match(haystack.as_bytes());
+            prop_assert_eq!(
+                last_found.map(|m| (m.start, m.end)),
+                last.map(|m| (m.start, m.end))
+            );
+        }
+    }
+}
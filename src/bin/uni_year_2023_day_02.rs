@@ -1,5 +1,7 @@
 use std::fs;
 
+use advent_of_code::decimal::Decimal;
+
 enum Gender {
     Male,
     Female
@@ -9,39 +11,20 @@ struct Elf {
     name: String,
     age: u32,
     gender: Gender,
-    production: f64,
+    production: Decimal,
 }
 
 fn parse_elf(line: &str) -> Option<Elf> {
     let mut parts = line.split(", ");
 
     if let (Some(name), Some(age), Some(gender), Some(production)) = (
-        parts.next().unwrap().split(": ").nth(1),
-        parts.next().unwrap().split(": ").nth(1),
-        parts.next().unwrap().split(": ").nth(1),
-        parts.next().unwrap().split(": ").nth(1)
+        parts.next()?.split(": ").nth(1),
+        parts.next()?.split(": ").nth(1),
+        parts.next()?.split(": ").nth(1),
+        parts.next()?.split(": ").nth(1),
     ) {
-        let decimal_length = 12;
-        let mut seen_decimal = false;
-        let mut decimal_index = 0;
-        let production = production
-            .replace(",", ".")
-            .chars()
-            .fold(String::new(), |mut acc, c| {
-                if c == '.' {
-                    seen_decimal = true;
-                    acc.push(c);
-                } else if !seen_decimal {
-                    acc.push(c);
-                } else if decimal_index < decimal_length {
-                    acc.push(c);
-                    decimal_index += 1;
-                }
-                acc
-            });
         if let Ok(age) = age.parse::<u32>() {
-            if let Ok(production) = production.parse::<f64>() {
-                println!("{}: {}", name, production);
+            if let Ok(production) = production.parse::<Decimal>() {
                 return Some(Elf {
                     name: name.to_string(),
                     age: age,
@@ -59,17 +42,66 @@ fn parse_elf(line: &str) -> Option<Elf> {
     None
 }
 
+// elves by descending production, ties keep their input order
+fn production_ranking(elfs: &[Elf]) -> Vec<&Elf> {
+    let mut ranking = elfs.iter().collect::<Vec<_>>();
+    ranking.sort_by(|a, b| b.production.cmp(&a.production));
+    ranking
+}
+
 fn main() {
     //read input from file
-    let mut input = fs::read_to_string("input.txt").unwrap();
+    let input = fs::read_to_string("input.txt").unwrap();
 
     let elfs: Vec<Elf> = input
-        .split("\n")
+        .lines()
+        .filter(|line| !line.is_empty())
         .map(|line| parse_elf(line).unwrap())
         .collect();
 
+    let total: Decimal = elfs.iter().map(|elf| elf.production).sum();
+    println!("total production: {}", total);
+
+    for (i, elf) in production_ranking(&elfs).iter().enumerate() {
+        println!("{}: {} {}", i + 1, elf.name, elf.production);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elfs() -> Vec<Elf> {
+        vec![
+            "Name: Alva, Age: 120, Gender: Female, Production: 0,1000000000000000000001",
+            "Name: Bo, Age: 87, Gender: Male, Production: 0.1",
+            "Name: Cim, Age: 301, Gender: Male, Production: 1.000,5",
+        ]
+        .into_iter()
+        .map(|line| parse_elf(line).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn parse_elf_test() {
+        let elf = parse_elf("Name: Cim, Age: 301, Gender: Male, Production: 1.000,5").unwrap();
+
+        assert_eq!((elf.name.as_str(), elf.age), ("Cim", 301));
+        assert_eq!(elf.production, "1000.5".parse().unwrap());
+        assert!(parse_elf("Name: Cim, Age: 301").is_none());
+    }
+
+    #[test]
+    fn production_test() {
+        let elfs = elfs();
 
-    for (i, elf) in elfs.iter().enumerate() {
-        println!("{}: {}", i, elf.name);
+        let total: Decimal = elfs.iter().map(|elf| elf.production).sum();
+        assert_eq!(total.to_string(), "1000.7000000000000000000001");
+
+        let names = production_ranking(&elfs)
+            .iter()
+            .map(|elf| elf.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Cim", "Alva", "Bo"]);
     }
-}
\ No newline at end of file
+}
@@ -1,62 +1,53 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+
+use advent_of_code::distinct::{first_distinct_window, StreamMarkers};
 use aoc_helper::{AocDay, Puzzle};
 
-struct RingBuffer<T> {
-    buffer: Vec<T>,
-    write_index: usize,
+fn solution(input: String, marker_length: usize) -> u32 {
+    first_distinct_window(input.chars(), marker_length).unwrap_or(0) as u32
 }
 
-impl <T> RingBuffer<T> {
-    pub fn new(size: usize) -> Self {
-        Self {
-            buffer: Vec::with_capacity(size),
-            write_index: 0,
+// prints the byte offset after every marker, reading the capture in constant memory
+fn run_scan(args: &[String]) {
+    let marker_length = match args.first().and_then(|x| x.parse::<usize>().ok()) {
+        Some(marker_length) if marker_length > 0 => marker_length,
+        _ => {
+            println!(
+                "Usage: year_2022_day_06 scan <marker length> [capture path, stdin if omitted]"
+            );
+            return;
         }
-    }
-
-    pub fn push(&mut self, item: T) {
-        if self.buffer.len() < self.buffer.capacity() {
-            self.buffer.push(item);
-        } else {
-            self.buffer[self.write_index] = item;
-            self.write_index = (self.write_index + 1) % self.buffer.capacity();
-        }
-    }
-
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.buffer.iter()
-    }
-}
-
-fn solution(input: String, marker_length: usize) -> u32 {
-    let mut ring_buffer = RingBuffer::<char>::new(marker_length);
-
-    for c in input.chars().take(marker_length - 1) {
-        ring_buffer.push(c);
-    }
-
-    let solution: u32 = 0;
-    
-    for (i, c) in input.chars().enumerate().skip(marker_length - 1) {
-        ring_buffer.push(c);
+    };
 
-        let mut bit_array = [false; 26];
+    let reader: Box<dyn Read> = match args.get(1) {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(file),
+            Err(err) => {
+                println!("Error while opening {}: {}", path, err);
+                return;
+            }
+        },
+        None => Box::new(io::stdin().lock()),
+    };
 
-        let is_distinct = ring_buffer.iter().fold(true,|acc, c| {
-            let bit_array_index = c.to_ascii_lowercase() as usize - 'a' as usize;
-            let bit_array_value = bit_array[bit_array_index];
-            bit_array[bit_array_index] = true;
-            return acc && !bit_array_value;
-        });
-
-        if is_distinct {
-            return (i + 1) as u32;
+    let mut output = BufWriter::new(io::stdout().lock());
+    for marker in StreamMarkers::new(reader, marker_length) {
+        let written = marker.and_then(|offset| writeln!(output, "{}", offset));
+        if let Err(err) = written {
+            println!("Error while scanning: {}", err);
+            return;
         }
     }
-
-    return solution;
 }
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("scan") {
+        run_scan(&args[2..]);
+        return;
+    }
+
     let mut aoc_day = AocDay::new(2022, 6);
     let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution(x, 4));
     let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution(x, 14));
@@ -66,4 +57,36 @@ fn main() {
 
     _result_part_1.map_err(|err| println!("Error while running part 1: {}", err)).unwrap();
     _result_part_2.map_err(|err| println!("Error while running part 2: {}", err)).unwrap();
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solution_test() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string();
+
+        assert_eq!(solution(input.clone(), 4), 7);
+        assert_eq!(solution(input, 14), 19);
+    }
+
+    #[test]
+    fn solution_examples_test() {
+        assert_eq!(solution("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string(), 4), 5);
+        assert_eq!(solution("nppdvjthqldpwncqszvftbrmjlhg".to_string(), 4), 6);
+        assert_eq!(
+            solution("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string(), 14),
+            29
+        );
+        assert_eq!(
+            solution("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string(), 14),
+            26
+        );
+    }
+
+    #[test]
+    fn solution_non_letters_test() {
+        assert_eq!(solution("AAbB1!".to_string(), 4), 5);
+    }
+}
@@ -1,5 +1,122 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use advent_of_code::aho_corasick::AhoCorasick;
 use aoc_helper::{AocDay, Puzzle};
 
+const DICTIONARIES: &str = include_str!("../../data/number_words.txt");
+
+// chosen in `main`, the puzzles only take plain function pointers
+static DICTIONARY: OnceLock<Dictionary> = OnceLock::new();
+
+#[derive(Debug, PartialEq)]
+enum DictionaryError {
+    MissingSection(usize),
+    InvalidEntry(usize),
+    UnknownLanguage(String),
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DictionaryError::MissingSection(line) => {
+                write!(f, "line {}: word before any [language] section", line)
+            }
+            DictionaryError::InvalidEntry(line) => {
+                write!(f, "line {}: expected `word = digit` with a digit 1-9", line)
+            }
+            DictionaryError::UnknownLanguage(language) => {
+                write!(f, "no dictionary for language {:?}", language)
+            }
+        }
+    }
+}
+
+// number words and their digits, the digits 1-9 themselves included
+struct Dictionary {
+    words: Vec<(String, u32)>,
+    automaton: AhoCorasick,
+}
+
+impl Dictionary {
+    fn new(words: Vec<(String, u32)>) -> Self {
+        let words = (1..=9)
+            .map(|digit| (digit.to_string(), digit))
+            .chain(words)
+            .collect::<Vec<_>>();
+        let automaton = AhoCorasick::new(words.iter().map(|(word, _)| word));
+        Dictionary { words, automaton }
+    }
+
+    fn english() -> Self {
+        let mut dictionaries = parse_dictionaries(DICTIONARIES).unwrap();
+        Dictionary::new(dictionaries.remove("en").unwrap())
+    }
+
+    fn digit(&self, pattern: usize) -> u32 {
+        self.words[pattern].1
+    }
+}
+
+// `[language]` sections of `word = digit` lines, `#` starts a comment
+fn parse_dictionaries(
+    input: &str,
+) -> Result<BTreeMap<String, Vec<(String, u32)>>, DictionaryError> {
+    let mut dictionaries = BTreeMap::new();
+    let mut language: Option<String> = None;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            language = Some(name.trim().to_string());
+            dictionaries
+                .entry(name.trim().to_string())
+                .or_insert_with(Vec::new);
+            continue;
+        }
+
+        let language = language
+            .as_ref()
+            .ok_or(DictionaryError::MissingSection(index + 1))?;
+        let entry = line.split_once('=').and_then(|(word, digit)| {
+            let digit = digit
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|x| (1..=9).contains(x))?;
+            let word = word.trim();
+            (!word.is_empty()).then(|| (word.to_string(), digit))
+        });
+        let entry = entry.ok_or(DictionaryError::InvalidEntry(index + 1))?;
+        dictionaries.get_mut(language).unwrap().push(entry);
+    }
+
+    Ok(dictionaries)
+}
+
+fn load_dictionary(path: Option<&str>, language: &str) -> Result<Dictionary, String> {
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|err| format!("Error while reading {}: {}", path, err))?,
+        None => DICTIONARIES.to_string(),
+    };
+    let mut dictionaries = parse_dictionaries(&input).map_err(|err| err.to_string())?;
+
+    match dictionaries.remove(language) {
+        Some(words) => Ok(Dictionary::new(words)),
+        None => Err(format!(
+            "{} (available: {})",
+            DictionaryError::UnknownLanguage(language.to_string()),
+            dictionaries.keys().cloned().collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
 fn solution_1(input: String) -> u32 {
     let mut solution: u32 = 0;
 
@@ -19,68 +136,59 @@ fn solution_1(input: String) -> u32 {
     return solution;
 }
 
-fn solution_2(input: String) -> u32 {
+fn decode(input: &str, dictionary: &Dictionary) -> u32 {
     let mut solution: u32 = 0;
 
-    let dictionary: Vec<(&str, u32)> = vec![
-        ("one", 1),
-        ("two", 2),
-        ("three", 3),
-        ("four", 4),
-        ("five", 5),
-        ("six", 6),
-        ("seven", 7),
-        ("eight", 8),
-        ("nine", 9),
-    ];
-
     for line in input.lines() {
-        let mut first_digit : Option<u32> = None;
-        let mut last_digit : Option<u32> = None;
-
-        for i in 0..line.len() {
-            let mut digit: Option<u32> = None;
-
-            digit = dictionary.iter().fold(None, |acc, (word, value)| {
-                if line[i..].starts_with(word) {
-                    return Some(*value);
-                } else {
-                    return acc;
-                }
-            });
-
-            if !digit.is_some() {
-                digit = line.chars().nth(i).unwrap().to_digit(10);
-            }
-
-            match (digit, first_digit) {
-                (Some(digit), None) => {
-                    first_digit = Some(digit);
-                },
-                (Some(digit), Some(_)) => {
-                    last_digit = Some(digit);
-                },
-                _ => {},
-            }
+        let first_digit = dictionary.automaton.first_match(line.as_bytes());
+        let last_digit = dictionary.automaton.last_match(line.as_bytes());
 
+        if let (Some(first_digit), Some(last_digit)) = (first_digit, last_digit) {
+            solution +=
+                10 * dictionary.digit(first_digit.pattern) + dictionary.digit(last_digit.pattern);
         }
+    }
 
-        match (first_digit, last_digit) {
-            (Some(first_digit), Some(last_digit)) => {
-                solution += 10 * first_digit + last_digit;
-            },
-            (Some(first_digit), None) => {
-                solution += 10 * first_digit + first_digit;
-            },
-            _ => {},
-        }
+    solution
+}
 
+fn solution_2(input: String) -> u32 {
+    decode(&input, DICTIONARY.get_or_init(Dictionary::english))
+}
+
+// `--lang <code>` picks the number words, `--dictionary <path>` replaces data/number_words.txt
+fn parse_args(args: &[String]) -> Option<(String, Option<String>)> {
+    let mut language = "en".to_string();
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => language = args.next()?.clone(),
+            "--dictionary" => path = Some(args.next()?.clone()),
+            _ => return None,
+        }
     }
 
-    return solution;
+    Some((language, path))
 }
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let Some((language, path)) = parse_args(&args[1..]) else {
+        println!("Usage: year_2023_day_01 [--lang <en|no|de|...>] [--dictionary <path>]");
+        return;
+    };
+    match load_dictionary(path.as_deref(), &language) {
+        Ok(dictionary) => {
+            let _ = DICTIONARY.set(dictionary);
+        }
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    }
+
     let mut aoc_day = AocDay::new(2023, 1);
     let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution_1(x));
     let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution_2(x));
@@ -99,3 +207,70 @@ fn main() {
         println!("Error while running part 2: {}", err);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solution_1_test() {
+        let input = vec!["1abc2", "pqr3stu8vwx", "a1b2c3d4e5f", "treb7uchet"].join("\n");
+
+        assert_eq!(solution_1(input), 142);
+    }
+
+    #[test]
+    fn solution_2_test() {
+        let input = vec![
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "4nineeightseven2",
+            "zoneight234",
+            "7pqrstsixteen",
+        ]
+        .join("\n");
+
+        assert_eq!(solution_2(input), 281);
+    }
+
+    #[test]
+    fn dictionaries_test() {
+        let dictionaries = parse_dictionaries(DICTIONARIES).unwrap();
+
+        assert_eq!(dictionaries.keys().collect::<Vec<_>>(), ["de", "en", "no"]);
+        assert_eq!(dictionaries["en"].len(), 9);
+        assert!(dictionaries["no"].contains(&("syv".to_string(), 7)));
+
+        assert_eq!(
+            parse_dictionaries("one = 1"),
+            Err(DictionaryError::MissingSection(1))
+        );
+        assert_eq!(
+            parse_dictionaries("[en]\n# ten\nten = 10"),
+            Err(DictionaryError::InvalidEntry(3))
+        );
+        assert!(load_dictionary(None, "fr").is_err());
+    }
+
+    #[test]
+    fn languages_test() {
+        let norwegian = load_dictionary(None, "no").unwrap();
+        // `en` is a prefix of `ett` and `sju`/`syv`/`seks` share their first letter
+        assert_eq!(decode("xettox\nsyvåtte\nseksju", &norwegian), 12 + 78 + 67);
+        assert_eq!(decode("one2", &norwegian), 22);
+
+        let german = load_dictionary(None, "de").unwrap();
+        assert_eq!(decode("zweinsechs\nfünf", &german), 26 + 55);
+    }
+
+    #[test]
+    fn overlapping_words_test() {
+        assert_eq!(solution_2("twone".to_string()), 21);
+        assert_eq!(solution_2("xoneightx".to_string()), 18);
+        assert_eq!(solution_2("five".to_string()), 55);
+        assert_eq!(solution_2("none".to_string()), 11);
+        assert_eq!(solution_2("xyz".to_string()), 0);
+    }
+}
@@ -1,211 +1,447 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use advent_of_code::byte_parsing::{skip_whitespace, split, split_once, tag, unsigned};
+use advent_of_code::parsing::{self, comma_list, labelled, report, semicolon_list, Diagnostic};
 use aoc_helper::{AocDay, Puzzle};
+use nom::{
+    character::complete::{alpha1, space0},
+    combinator::{all_consuming, cut},
+    error::{context, VerboseError},
+    sequence::{delimited, pair, separated_pair, terminated},
+    IResult, Parser,
+};
+
+const INPUT_PATH: &str = "inputs/2023/day2.txt";
+
+// chosen in `main`, the puzzles only take plain function pointers
+static BAG: OnceLock<Bag> = OnceLock::new();
+
+// cube counts by colour, any colour name is allowed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Bag {
+    cubes: BTreeMap<String, u32>,
+}
 
-pub type IResult<I, O, E = String> = Result<(I, O), E>;
+// the colour names borrow from the input
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Round<'a> {
+    cubes: Vec<(&'a str, u32)>,
+}
 
-enum Color {
-    Red,
-    Green,
-    Blue,
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Game<'a> {
+    id: u32,
+    rounds: Vec<Round<'a>>,
 }
 
-fn parse_number(input: &str) -> IResult<&str, u32> {
-    let digit_count = input.chars().take_while(|c| c.is_digit(10)).count();
-    match input
-        .chars()
-        .take_while(|c| c.is_digit(10))
-        .collect::<String>()
-        .parse::<u32>()
-    {
-        Ok(number) => Ok((&input[digit_count..], number)),
-        Err(_) => Err("Error while parsing number".to_string()),
-    }
+// a colour of a round that drew more cubes than the bag holds, `round` counts from 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Violation {
+    round: usize,
+    color: String,
+    drawn: u32,
+    limit: u32,
 }
 
-fn parse_color(input: &str) -> IResult<&str, Color> {
-    match input.as_bytes().get(0) {
-        Some(b'r') => Ok((&input[3..], Color::Red)),
-        Some(b'g') => Ok((&input[5..], Color::Green)),
-        Some(b'b') => Ok((&input[4..], Color::Blue)),
-        _ => Err("Error while parsing color".to_string()),
-    }
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Explanation {
+    id: u32,
+    violations: Vec<Violation>,
+    minimum_bag: Bag,
 }
 
-fn parse_color_count(input: &str) -> IResult<&str, (Color, u32)> {
-    let (input, count) = parse_number(input)?;
-    let input = &input[1..];
-    let (input, color) = parse_color(input)?;
+impl Bag {
+    fn standard() -> Self {
+        Bag::from_iter([("red", 12), ("green", 13), ("blue", 14)])
+    }
 
-    Ok((input, (color, count)))
-}
+    // colours missing from the bag have no cubes
+    fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
 
-fn is_color_possible(color: Color, count: u32) -> bool {
-    match color {
-        Color::Red => count <= 12,
-        Color::Green => count <= 13,
-        Color::Blue => count <= 14,
+    // product of the counts of the colours in `colors`, so a colour that was never drawn makes it 0
+    fn power(&self, colors: &Bag) -> u32 {
+        if colors.cubes.is_empty() {
+            return 0;
+        }
+        colors.cubes.keys().map(|color| self.count(color)).product()
     }
 }
 
-fn parse_is_color_possible(input: &str) -> IResult<&str, bool> {
-    let (input, (color, count)) = parse_color_count(input)?;
-    Ok((input, is_color_possible(color, count)))
+impl<'a> FromIterator<(&'a str, u32)> for Bag {
+    fn from_iter<I: IntoIterator<Item = (&'a str, u32)>>(iter: I) -> Self {
+        let mut bag = Bag::default();
+        for (color, count) in iter {
+            let entry = bag.cubes.entry(color.to_string()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+        bag
+    }
 }
 
-fn parse_is_round_possible(input: &str) -> IResult<&str, bool> {
-    let (mut input_result, mut possible) = parse_is_color_possible(input)?;
-    while input_result.starts_with(", ") && possible {
-        input_result = &input_result[2..];
+impl Round<'_> {
+    fn is_possible(&self, bag: &Bag) -> bool {
+        self.cubes
+            .iter()
+            .all(|(color, count)| *count <= bag.count(color))
+    }
+}
 
-        let color_possible: bool;
-        (input_result, color_possible) = parse_is_color_possible(input_result)?;
-        possible &= color_possible;
+impl Game<'_> {
+    fn is_possible(&self, bag: &Bag) -> bool {
+        self.rounds.iter().all(|round| round.is_possible(bag))
     }
 
-    if !possible {
-        match input_result.find(|c| c == '\n' || c == ';') {
-            Some(skip_count) => input_result = &input_result[skip_count..],
-            None => input_result = &input_result[input_result.len()..],
+    fn violations(&self, bag: &Bag) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (index, round) in self.rounds.iter().enumerate() {
+            for (color, drawn) in &round.cubes {
+                let limit = bag.count(color);
+                if *drawn > limit {
+                    violations.push(Violation {
+                        round: index + 1,
+                        color: color.to_string(),
+                        drawn: *drawn,
+                        limit,
+                    });
+                }
+            }
         }
+        violations
     }
 
-    Ok((input_result, possible))
+    // the smallest bag every round could have been drawn from
+    fn minimum_bag(&self) -> Bag {
+        self.rounds
+            .iter()
+            .flat_map(|round| round.cubes.iter())
+            .copied()
+            .collect()
+    }
 }
 
-fn parse_are_rounds_possible(input: &str) -> IResult<&str, bool> {
-    let (mut input_result, mut possible) = parse_is_round_possible(input)?;
-    while input_result.starts_with("; ") && possible {
-        input_result = &input_result[2..];
-
-        let round_possible: bool;
-        (input_result, round_possible) = parse_is_round_possible(input_result)?;
-        possible &= round_possible;
+impl Violation {
+    fn excess(&self) -> u32 {
+        self.drawn - self.limit
     }
+}
 
-    if !possible {
-        match input_result.find('\n') {
-            Some(skip_count) => input_result = &input_result[skip_count..],
-            None => input_result = &input_result[input_result.len()..],
-        }
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes = self
+            .cubes
+            .iter()
+            .map(|(color, count)| format!("{}={}", color, count))
+            .collect::<Vec<_>>();
+        write!(f, "{}", cubes.join(","))
     }
-
-    Ok((input_result, possible))
 }
 
-fn parse_game_id(input: &str) -> IResult<&str, u32> {
-    let input = &input[5..];
-    let (input, game_id) = parse_number(input)?;
-    Ok((input, game_id))
+fn parse_color_count(input: &[u8]) -> Option<(&str, u32)> {
+    let (count, input) = unsigned(skip_whitespace(input))?;
+    let input = skip_whitespace(input);
+    let length = input.iter().take_while(|c| c.is_ascii_alphabetic()).count();
+    let (color, rest) = input.split_at(length);
+
+    let complete = length > 0 && skip_whitespace(rest).is_empty();
+    // ascii letters are always valid utf-8
+    let color = std::str::from_utf8(color).ok()?;
+    complete.then_some((color, count))
 }
 
-fn parse_is_game_possible(input: &str) -> IResult<&str, (u32, bool)> {
-    let possible: bool;
-    let (mut input_result, game_id) = parse_game_id(input)?;
-    input_result = &input_result[2..];
-    (input_result, possible) = parse_are_rounds_possible(input_result)?;
-    Ok((input_result, (game_id, possible)))
+fn parse_round(input: &[u8]) -> Option<Round<'_>> {
+    let cubes = split(input, b',')
+        .map(parse_color_count)
+        .collect::<Option<Vec<_>>>()?;
+    Some(Round { cubes })
 }
 
-fn parse_round_minimum_set(input: &str) -> IResult<&str, (u32, u32, u32)> {
-    let mut rgb_set = (0, 0, 0);
-    let mut input_result = input;
-    let mut color: Color;
-    let mut count: u32;
+fn parse_game(input: &[u8]) -> Option<Game<'_>> {
+    let input = tag(input, b"Game")?;
+    let (id, input) = unsigned(skip_whitespace(input))?;
+    let (_, rounds) = split_once(input, b':')?;
+    let rounds = split(rounds, b';')
+        .map(parse_round)
+        .collect::<Option<Vec<_>>>()?;
+    Some(Game { id, rounds })
+}
 
-    (input_result, (color, count)) = parse_color_count(input)?;
-    match color {
-        Color::Red => rgb_set.0 = rgb_set.0.max(count),
-        Color::Green => rgb_set.1 = rgb_set.1.max(count),
-        Color::Blue => rgb_set.2 = rgb_set.2.max(count),
-    }
-    while input_result.starts_with(", ") {
-        input_result = &input_result[2..];
-        (input_result, (color, count)) = parse_color_count(input_result)?;
-        match color {
-            Color::Red => rgb_set.0 = rgb_set.0.max(count),
-            Color::Green => rgb_set.1 = rgb_set.1.max(count),
-            Color::Blue => rgb_set.2 = rgb_set.2.max(count),
-        }
-    }
+fn parse_color_count_nom(input: &str) -> IResult<&str, (&str, u32), VerboseError<&str>> {
+    let (input, (count, color)) = delimited(
+        space0,
+        separated_pair(parsing::unsigned, space0, context("a colour", alpha1)),
+        space0,
+    )(input)?;
 
-    Ok((input_result, rgb_set))
+    Ok((input, (color, count)))
 }
 
-fn parse_rounds_minimum_set_power(input: &str) -> IResult<&str, u32> {
-    let mut input_result = input;
-    let mut round_minimum_set: (u32, u32, u32);
-    let mut round_minimum_set_result: (u32, u32, u32);
+fn parse_game_nom(input: &str) -> IResult<&str, Game<'_>, VerboseError<&str>> {
+    // `cut` keeps the lists from backtracking, so a bad item is reported where it fails
+    let round = comma_list(cut(parse_color_count_nom)).map(|cubes| Round { cubes });
+    let (input, (id, rounds)) = pair(
+        context("`Game <number>:`", labelled("Game")),
+        terminated(semicolon_list(cut(round)), space0),
+    )(input)?;
 
-    (input_result, round_minimum_set) = parse_round_minimum_set(input_result)?;
-    round_minimum_set_result = round_minimum_set;
-
-    while input_result.starts_with("; ") {
-        input_result = &input_result[2..];
-        (input_result, round_minimum_set) = parse_round_minimum_set(input_result)?;
-        round_minimum_set_result.0 = round_minimum_set_result.0.max(round_minimum_set.0);
-        round_minimum_set_result.1 = round_minimum_set_result.1.max(round_minimum_set.1);
-        round_minimum_set_result.2 = round_minimum_set_result.2.max(round_minimum_set.2);
-    }
+    Ok((input, Game { id, rounds }))
+}
 
-    let result = round_minimum_set_result.0 * round_minimum_set_result.1 * round_minimum_set_result.2;
-    Ok((input_result, result))
+// the byte parser reads well formed lines, the nom parser only runs to explain the others
+fn parse_games<'a>(input: &'a str, source: &str) -> Result<Vec<Game<'a>>, Diagnostic> {
+    input
+        .lines()
+        .map(|line| match parse_game(line.as_bytes()) {
+            Some(game) => Ok(game),
+            None => all_consuming(parse_game_nom)(line)
+                .map(|(_, game)| game)
+                .map_err(|error| Diagnostic::from_nom(source, input, error)),
+        })
+        .collect()
 }
 
-fn parse_game_minimum_set_power(input: &str) -> IResult<&str, u32> {
-    let (input, _) = parse_game_id(input)?;
-    let input = &input[2..];
-    let (input, result) = parse_rounds_minimum_set_power(input)?;
-    Ok((input, result))
+// `red=12,green=13` or `red = 12` lines, `#` starts a comment
+fn parse_bag(input: &str) -> Option<Bag> {
+    let mut cubes = Vec::new();
+    for entry in input.split([',', '\n']) {
+        let entry = entry.split('#').next().unwrap().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let (color, count) = entry.split_once('=')?;
+        let color = color.trim();
+        if color.is_empty() || !color.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        cubes.push((color, count.trim().parse().ok()?));
+    }
+    Some(cubes.into_iter().collect())
 }
 
-fn solution_1(input: &str) -> u32 {
-    let mut solution: u32 = 0;
+fn possible_id_sum(games: &[Game], bag: &Bag) -> u32 {
+    games
+        .iter()
+        .filter(|game| game.is_possible(bag))
+        .map(|game| game.id)
+        .sum()
+}
 
-    let mut input_result: &str;
-    let mut game_id: u32;
-    let mut possible: bool;
+fn minimum_power_sum(games: &[Game], bag: &Bag) -> u32 {
+    games.iter().map(|game| game.minimum_bag().power(bag)).sum()
+}
 
-    (input_result, (game_id, possible)) = parse_is_game_possible(input).unwrap();
+// the impossible games, each with what broke the bag and the bag it needed
+fn explain(games: &[Game], bag: &Bag) -> Vec<Explanation> {
+    games
+        .iter()
+        .filter(|game| !game.is_possible(bag))
+        .map(|game| Explanation {
+            id: game.id,
+            violations: game.violations(bag),
+            minimum_bag: game.minimum_bag(),
+        })
+        .collect()
+}
 
-    if possible {
-        solution += game_id;
+fn explain_table(explanations: &[Explanation]) -> String {
+    let mut rows = vec![[
+        "game",
+        "round",
+        "colour",
+        "drawn",
+        "bag",
+        "excess",
+        "minimal bag",
+    ]
+    .map(String::from)];
+    for explanation in explanations {
+        for (index, violation) in explanation.violations.iter().enumerate() {
+            let minimum_bag = match index {
+                0 => explanation.minimum_bag.to_string(),
+                _ => String::new(),
+            };
+            rows.push([
+                explanation.id.to_string(),
+                violation.round.to_string(),
+                violation.color.clone(),
+                violation.drawn.to_string(),
+                violation.limit.to_string(),
+                violation.excess().to_string(),
+                minimum_bag,
+            ]);
+        }
     }
 
-    while input_result.chars().nth(0) == Some('\n') {
-        input_result = &input_result[1..];
+    let widths = (0..7)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap())
+        .collect::<Vec<_>>();
+    rows.iter()
+        .map(|row| {
+            let cells = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-        (input_result, (game_id, possible)) = parse_is_game_possible(input_result).unwrap();
+// colour names are ascii letters only, so nothing needs escaping
+fn explain_json(explanations: &[Explanation]) -> String {
+    let games = explanations
+        .iter()
+        .map(|explanation| {
+            let violations = explanation
+                .violations
+                .iter()
+                .map(|violation| {
+                    format!(
+                        "{{\"round\":{},\"colour\":\"{}\",\"drawn\":{},\"bag\":{},\"excess\":{}}}",
+                        violation.round,
+                        violation.color,
+                        violation.drawn,
+                        violation.limit,
+                        violation.excess()
+                    )
+                })
+                .collect::<Vec<_>>();
+            let minimum_bag = explanation
+                .minimum_bag
+                .cubes
+                .iter()
+                .map(|(color, count)| format!("\"{}\":{}", color, count))
+                .collect::<Vec<_>>();
+            format!(
+                "{{\"game\":{},\"violations\":[{}],\"minimal_bag\":{{{}}}}}",
+                explanation.id,
+                violations.join(","),
+                minimum_bag.join(",")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", games.join(","))
+}
 
-        if possible {
-            solution += game_id;
-        }
-    }
+// `source` names where the input came from in diagnostics
+fn solution_1(input: &str, source: &str) -> Result<u32, Diagnostic> {
+    let games = parse_games(input, source)?;
 
-    return solution;
+    Ok(possible_id_sum(&games, BAG.get_or_init(Bag::standard)))
 }
 
-fn solution_2(input: String) -> u32 {
-    let mut solution = 0;
+fn solution_2(input: String, source: &str) -> Result<u32, Diagnostic> {
+    let games = parse_games(&input, source)?;
 
-    let mut input_result: &str;
-    let mut result: u32;
+    Ok(minimum_power_sum(&games, BAG.get_or_init(Bag::standard)))
+}
 
+// `--bag red=12,green=13,blue=14` or `--bag-file <path>` with one `colour = count` per line
+fn parse_args(args: &[String]) -> Result<Bag, String> {
+    let mut bag = Bag::standard();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (source, value) = match (arg.as_str(), args.next()) {
+            ("--bag", Some(value)) => (value.clone(), value.clone()),
+            ("--bag-file", Some(path)) => {
+                let contents = std::fs::read_to_string(path)
+                    .map_err(|err| format!("Error while reading {}: {}", path, err))?;
+                (path.clone(), contents)
+            }
+            _ => {
+                return Err(
+                    "Usage: year_2023_day_02 [--bag <colour>=<count>,...] [--bag-file <path>]"
+                        .to_string(),
+                )
+            }
+        };
+        bag = parse_bag(&value).ok_or_else(|| format!("Error in bag {}", source))?;
+    }
 
-    (input_result, result) = parse_game_minimum_set_power(&input).unwrap();
-    solution += result;
+    Ok(bag)
+}
 
-    while input_result.chars().nth(0) == Some('\n') {
-        input_result = &input_result[1..];
-        (input_result, result) = parse_game_minimum_set_power(input_result).unwrap();
-        solution += result;
+// `explain [--json] [--bag ...] [input path]` lists why each impossible game is impossible
+fn run_explain(args: &[String]) {
+    let usage = "Usage: year_2023_day_02 explain [--json] [--bag <colour>=<count>,...] \
+                 [--bag-file <path>] [input path]";
+    let mut json = false;
+    let mut bag_args = Vec::new();
+    let mut input_path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--bag" | "--bag-file" => {
+                bag_args.push(arg.clone());
+                bag_args.extend(args.next().cloned());
+            }
+            path if input_path.is_none() && !path.starts_with("--") => {
+                input_path = Some(path.to_string())
+            }
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
     }
 
-    return solution;
+    let bag = match parse_args(&bag_args) {
+        Ok(bag) => bag,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let input_path = input_path.unwrap_or_else(|| INPUT_PATH.to_string());
+
+    match std::fs::read_to_string(&input_path) {
+        Ok(input) => {
+            let games = match parse_games(&input, &input_path) {
+                Ok(games) => games,
+                Err(diagnostic) => {
+                    println!("{}", diagnostic);
+                    return;
+                }
+            };
+            let explanations = explain(&games, &bag);
+            if json {
+                println!("{}", explain_json(&explanations));
+            } else {
+                println!("{}", explain_table(&explanations));
+            }
+        }
+        Err(err) => println!("Error while reading {}: {}", input_path, err),
+    }
 }
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("explain") {
+        run_explain(&args[2..]);
+        return;
+    }
+
+    match parse_args(&args[1..]) {
+        Ok(bag) => {
+            let _ = BAG.set(bag);
+        }
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    }
+
     let mut aoc_day = AocDay::new(2023, 2);
-    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution_1(&x));
-    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution_2(x));
+    aoc_day.input(INPUT_PATH);
+    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| report(solution_1(&x, INPUT_PATH)));
+    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| report(solution_2(x, INPUT_PATH)));
 
     let _result_part_1 = aoc_day.run(&aoc_puzzle_part_1);
 
@@ -219,3 +455,163 @@ fn main() {
         println!("Error while running part 2: {}", err);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_input() -> String {
+        vec![
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn parse_game_test() {
+        let game = parse_game(b"Game 12: 3 blue, 4 red; 13 red").unwrap();
+
+        assert_eq!(game.id, 12);
+        assert_eq!(game.rounds.len(), 2);
+        assert_eq!(game.rounds[1].cubes, [("red", 13)]);
+        assert!(!game.is_possible(&Bag::standard()));
+
+        assert!(parse_game(b"Game 12: 3 blue 4 red").is_none());
+        assert!(parse_game(b"Game 12: 3 blue, red").is_none());
+    }
+
+    #[test]
+    fn open_colors_test() {
+        let game = parse_game(b"Game 1: 3 blue, 2 purple; 1 purple, 4 red").unwrap();
+
+        assert!(!game.is_possible(&Bag::standard()));
+        assert!(game.is_possible(&parse_bag("red=4, blue=3, purple=2").unwrap()));
+        assert_eq!(
+            game.minimum_bag(),
+            Bag::from_iter([("blue", 3), ("purple", 2), ("red", 4)])
+        );
+        // no green cube was drawn
+        assert_eq!(game.minimum_bag().power(&Bag::standard()), 0);
+        assert_eq!(
+            game.minimum_bag()
+                .power(&parse_bag("red=4, blue=3, purple=2").unwrap()),
+            24
+        );
+    }
+
+    #[test]
+    fn power_missing_color_test() {
+        let games = parse_games(
+            "Game 1: 3 blue, 4 red\nGame 2: 1 red; 2 green, 3 blue",
+            "example",
+        )
+        .unwrap();
+
+        assert_eq!(games[0].minimum_bag().power(&Bag::standard()), 0);
+        assert_eq!(minimum_power_sum(&games, &Bag::standard()), 6);
+        assert_eq!(Bag::default().power(&Bag::standard()), 0);
+        assert_eq!(games[0].minimum_bag().power(&Bag::default()), 0);
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let input = example_input().replace("2 green, 6 blue", "2 green, 6");
+
+        let diagnostic = solution_1(&input, "games.txt").unwrap_err();
+        assert_eq!(diagnostic.file, "games.txt");
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 41));
+        assert_eq!(diagnostic.expected, "a colour");
+
+        let diagnostic = solution_2("Game x: 1 red".to_string(), "example").unwrap_err();
+        assert_eq!(
+            (diagnostic.column, diagnostic.expected.as_str()),
+            (6, "`Game <number>:`")
+        );
+    }
+
+    #[test]
+    fn parse_bag_test() {
+        assert_eq!(
+            parse_bag("# limits\nred = 1\nblue = 2\n"),
+            Some(Bag::from_iter([("red", 1), ("blue", 2)]))
+        );
+        assert_eq!(parse_bag("red=1,green"), None);
+        assert_eq!(parse_bag("red=x"), None);
+
+        let bag = parse_args(&["--bag".to_string(), "red=20,green=20,blue=20".to_string()]);
+        assert_eq!(
+            possible_id_sum(
+                &parse_games(&example_input(), "example").unwrap(),
+                &bag.unwrap()
+            ),
+            15
+        );
+        assert!(parse_args(&["--bag".to_string()]).is_err());
+    }
+
+    #[test]
+    fn solution_1_test() {
+        assert_eq!(solution_1(&example_input(), "example"), Ok(8));
+    }
+
+    #[test]
+    fn solution_2_test() {
+        assert_eq!(solution_2(example_input(), "example"), Ok(2286));
+    }
+
+    #[test]
+    fn explain_test() {
+        let input = example_input();
+        let games = parse_games(&input, "example").unwrap();
+        let explanations = explain(&games, &Bag::standard());
+
+        assert_eq!(
+            explanations.iter().map(|x| x.id).collect::<Vec<_>>(),
+            [3, 4]
+        );
+        assert_eq!(
+            explanations[0].violations,
+            [Violation {
+                round: 1,
+                color: "red".to_string(),
+                drawn: 20,
+                limit: 12,
+            }]
+        );
+        assert_eq!(explanations[1].violations[0].round, 3);
+        assert_eq!(explanations[1].violations[0].excess(), 1);
+        assert!(games[2].is_possible(&explanations[0].minimum_bag));
+    }
+
+    #[test]
+    fn explain_output_test() {
+        let games = parse_games("Game 7: 13 red, 2 blue; 15 blue, 1 purple", "example").unwrap();
+        let explanations = explain(&games, &Bag::standard());
+
+        assert_eq!(
+            explain_table(&explanations),
+            vec![
+                "game  round  colour  drawn  bag  excess  minimal bag",
+                "7     1      red     13     12   1       blue=15,purple=1,red=13",
+                "7     2      blue    15     14   1",
+                "7     2      purple  1      0    1",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            explain_json(&explanations[..1]),
+            concat!(
+                "[{\"game\":7,\"violations\":[",
+                "{\"round\":1,\"colour\":\"red\",\"drawn\":13,\"bag\":12,\"excess\":1},",
+                "{\"round\":2,\"colour\":\"blue\",\"drawn\":15,\"bag\":14,\"excess\":1},",
+                "{\"round\":2,\"colour\":\"purple\",\"drawn\":1,\"bag\":0,\"excess\":1}",
+                "],\"minimal_bag\":{\"blue\":15,\"purple\":1,\"red\":13}}]"
+            )
+        );
+        assert_eq!(explain_json(&[]), "[]");
+    }
+}
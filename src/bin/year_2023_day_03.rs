@@ -1,340 +1,282 @@
-use core::num;
-use std::vec;
+use std::fmt;
+use std::ops::Range;
 
+use advent_of_code::byte_parsing::unsigned;
+use advent_of_code::grid::Grid;
+use advent_of_code::parsing::report;
 use aoc_helper::{AocDay, Puzzle};
 
-struct BitTable {
-    bit_map: Vec<bool>,
-    width: u32,
-    height: u32,
+#[derive(Debug, PartialEq, Eq)]
+struct Number {
+    value: u32,
+    x: usize,
+    y: usize,
+    length: usize,
+    // range of `Schematic::number_symbols`
+    symbols: Range<usize>,
 }
 
-impl BitTable {
-    fn new(width: u32, height: u32) -> Self {
-        Self {
-            bit_map: vec![false; (width * height) as usize],
-            width: width,
-            height: height,
-        }
-    }
-
-    fn set(&mut self, x: u32, y: u32, value: bool) {
-        if x < self.width && y < self.height {
-            let index: usize = (x + y * self.width) as usize;
-            self.bit_map[index] = value;
-        }
-    }
+#[derive(Debug, PartialEq, Eq)]
+struct Symbol {
+    kind: char,
+    x: usize,
+    y: usize,
+    // range of `Schematic::symbol_numbers`
+    numbers: Range<usize>,
+}
 
-    fn set_around(&mut self, x: u32, y: u32, value: bool) {
-        match (x, y) {
-            (0, 0) => {
-                self.set(x, y, value);
-                self.set(x + 1, y, value);
-                self.set(x, y + 1, value);
-                self.set(x + 1, y + 1, value);
-            }
-            (0, _) => {
-                self.set(x, y, value);
-                self.set(x, y - 1, value);
-                self.set(x, y + 1, value);
-                self.set(x + 1, y - 1, value);
-                self.set(x + 1, y, value);
-                self.set(x + 1, y + 1, value);
-            }
-            (_, 0) => {
-                self.set(x, y, value);
-                self.set(x - 1, y, value);
-                self.set(x + 1, y, value);
-                self.set(x - 1, y + 1, value);
-                self.set(x, y + 1, value);
-                self.set(x + 1, y + 1, value);
-            }
-            (_, _) => {
-                self.set(x, y, value);
-                self.set(x - 1, y - 1, value);
-                self.set(x, y - 1, value);
-                self.set(x + 1, y - 1, value);
-                self.set(x - 1, y, value);
-                self.set(x + 1, y, value);
-                self.set(x - 1, y + 1, value);
-                self.set(x, y + 1, value);
-                self.set(x + 1, y + 1, value);
-            }
-        }
-    }
+// a run of digits at `x`, `y` that does not fit into a `u32`
+#[derive(Debug, PartialEq, Eq)]
+struct NumberTooLarge {
+    x: usize,
+    y: usize,
+}
 
-    fn get(&self, x: u32, y: u32) -> bool {
-        if x <= self.width && y < self.height {
-            let index: usize = (x + y * self.width) as usize;
-            self.bit_map[index]
-        } else {
-            false
-        }
+impl fmt::Display for NumberTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "number at {},{} does not fit into a u32", self.x, self.y)
     }
 }
 
-struct FoundNumber {
-    number: u32,
-    indecies: Vec<u32>
-}
+impl std::error::Error for NumberTooLarge {}
 
-fn get_number_from_index(input: &str, index: usize) -> FoundNumber {
-    let mut right_offset = 0;
-    let mut left_offset = 0;
+// numbers and symbols of the engine, each linked to everything it touches, diagonals included
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    // the links of all numbers and of all symbols back to back, so parsing does not allocate
+    // per link
+    number_symbols: Vec<usize>,
+    symbol_numbers: Vec<usize>,
+}
 
-    while index + right_offset + 1 < input.len() {
-        if let Some(c) = input.chars().nth(index + right_offset + 1) {
-            if c.is_numeric() {
-                right_offset += 1;
-            } else {
-                break;
+impl Schematic {
+    fn parse(input: &str) -> Result<Self, NumberTooLarge> {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        let (mut width, mut height) = (0, 0);
+
+        // digits are ascii, other symbols may take several bytes but always one column
+        for (y, row) in input.lines().enumerate() {
+            let bytes = row.as_bytes();
+            let (mut offset, mut x) = (0, 0);
+            while offset < bytes.len() {
+                match bytes[offset] {
+                    b'.' => {
+                        offset += 1;
+                        x += 1;
+                    }
+                    b'0'..=b'9' => {
+                        let length = bytes[offset..]
+                            .iter()
+                            .take_while(|b| b.is_ascii_digit())
+                            .count();
+                        let (value, _) = unsigned::<u32>(&bytes[offset..offset + length])
+                            .ok_or(NumberTooLarge { x, y })?;
+                        numbers.push(Number {
+                            value,
+                            x,
+                            y,
+                            length,
+                            symbols: 0..0,
+                        });
+                        offset += length;
+                        x += length;
+                    }
+                    _ => {
+                        let kind = row[offset..].chars().next().unwrap();
+                        symbols.push(Symbol {
+                            kind,
+                            x,
+                            y,
+                            numbers: 0..0,
+                        });
+                        offset += kind.len_utf8();
+                        x += 1;
+                    }
+                }
             }
-        } else {
-            break;
+            width = width.max(x);
+            height = y + 1;
         }
-    }
 
-    while index - left_offset > 0 {
-        if let Some(c) = input.chars().nth(index - left_offset - 1) {
-            if c.is_numeric() {
-                left_offset += 1;
-            } else {
-                break;
-            }
-        } else {
-            break;
+        let mut symbol_at = Grid::filled(width as u32, height as u32, None);
+        for (index, symbol) in symbols.iter().enumerate() {
+            symbol_at.set(symbol.x as u32, symbol.y as u32, Some(index));
         }
-    }
 
-    let number_string = input
-        .chars()
-        .skip(index - left_offset)
-        .take(left_offset + right_offset + 1)
-        .collect::<String>();
-
-    FoundNumber { 
-        number: number_string.parse::<u32>().unwrap(),
-        indecies: (index - left_offset..index + right_offset + 1).map(|i| i as u32).collect()
-    }
-}
-
-fn get_numbers_around(line_above: &str, line: &str, line_below: &str, index: usize) -> Vec<u32> {
-    let mut numbers: Vec<u32> = Vec::new();
-    let scanned_indecies = [false; 8];
-
-    //check above
-    for i in index - 1..index + 1 {
-        if let Some(c) = line_above.chars().nth(i) && scanned_indecies[i as usize] {
-            if c.is_numeric() {
-                let found_number = get_number_from_index(line_above, i);
-                numbers.push(found_number.number);
-                for i in found_number.indecies {
-                    scanned_indecies[i as usize] = true;
+        let mut number_symbols = Vec::new();
+        let mut touching = vec![0; symbols.len()];
+        for number in &mut numbers {
+            let start = number_symbols.len();
+            for y in number.y.saturating_sub(1)..=number.y + 1 {
+                for x in number.x.saturating_sub(1)..=number.x + number.length {
+                    if let Some(&Some(symbol)) = symbol_at.get(x as u32, y as u32) {
+                        number_symbols.push(symbol);
+                        touching[symbol] += 1;
+                    }
                 }
             }
+            number.symbols = start..number_symbols.len();
         }
-    }
 
-    if let Some(c) = line_above.chars().nth(index) {
-        if c.is_numeric() {
-            let found_number = get_number_from_index(line_above, index);
-            numbers.push(found_number.number);
-            for i in found_number.indecies {
-                scanned_indecies[i as usize] = true;
+        // the same links grouped by symbol, each symbol lists its numbers in input order
+        let mut end = 0;
+        for (symbol, count) in symbols.iter_mut().zip(touching) {
+            symbol.numbers = end..end;
+            end += count;
+        }
+        let mut symbol_numbers = vec![0; number_symbols.len()];
+        for (index, number) in numbers.iter().enumerate() {
+            for &symbol in &number_symbols[number.symbols.clone()] {
+                let slots = &mut symbols[symbol].numbers;
+                symbol_numbers[slots.end] = index;
+                slots.end += 1;
             }
         }
-    }
 
-    numbers
-}
-
-pub type IResult<I, O, E = String> = Result<(I, O), E>;
-
-fn parse_number(input: &str) -> IResult<&str, u32> {
-    let digit_count = input.chars().take_while(|c| c.is_digit(10)).count();
-    match input
-        .chars()
-        .take_while(|c| c.is_digit(10))
-        .collect::<String>()
-        .parse::<u32>()
-    {
-        Ok(number) => Ok((&input[digit_count..], number)),
-        Err(_) => Err("Error while parsing number".to_string()),
+        Ok(Schematic {
+            numbers,
+            symbols,
+            number_symbols,
+            symbol_numbers,
+        })
     }
-}
 
-fn solution_1(input: &str) -> u32 {
-    let mut solution = 0;
-
-    let line_length = input.lines().nth(0).unwrap().len();
-    let mut bit_map: BitTable = BitTable::new(line_length as u32, input.lines().count() as u32);
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if !c.is_numeric() && c != '.' {
-                bit_map.set_around(x as u32, y as u32, true);
-            }
-        }
+    // numbers touching at least one symbol
+    fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|number| !number.symbols.is_empty())
     }
 
-    let mut input_result = &input[..];
-
-    let mut index = 0;
-    while input_result.len() > 0 {
-        let c = input_result.chars().nth(0).unwrap();
-        match c {
-            c if c.is_numeric() => {
-                let x = index % line_length;
-                let y = index / line_length;
-
-                let number: u32;
-                let digit_count = input_result.chars().take_while(|c| c.is_digit(10)).count();
+    fn orphan_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|number| number.symbols.is_empty())
+    }
 
-                (input_result, number) = parse_number(input_result).unwrap();
+    fn numbers_touching(&self, kind: char) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(move |number| self.symbols_of(number).any(|symbol| symbol.kind == kind))
+    }
 
-                let is_included = (0..digit_count).fold(false, |acc, next| {
-                    acc || bit_map.get(x as u32 + next as u32, y as u32)
-                });
+    // `*` symbols touching exactly `parts` numbers
+    fn gears(&self, parts: usize) -> impl Iterator<Item = &Symbol> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| symbol.kind == '*' && symbol.numbers.len() == parts)
+    }
 
-                if is_included {
-                    solution += number;
-                }
+    fn numbers_of<'a>(&'a self, symbol: &'a Symbol) -> impl Iterator<Item = &'a Number> {
+        self.symbol_numbers[symbol.numbers.clone()]
+            .iter()
+            .map(|&number| &self.numbers[number])
+    }
 
-                index += digit_count;
-            }
-            '\n' => {
-                input_result = &input_result[1..];
-            }
-            _ => {
-                input_result = &input_result[1..];
-                index += 1;
-            }
-        }
+    fn symbols_of<'a>(&'a self, number: &'a Number) -> impl Iterator<Item = &'a Symbol> {
+        self.number_symbols[number.symbols.clone()]
+            .iter()
+            .map(|&symbol| &self.symbols[symbol])
     }
+}
 
-    solution
+fn solution_1(input: &str) -> Result<u32, NumberTooLarge> {
+    Ok(Schematic::parse(input)?
+        .part_numbers()
+        .map(|number| number.value)
+        .sum())
 }
 
-fn solution_2(input: String) -> u32 {
-    let mut solution = 0;
-
-    let lines = input.lines().collect::<Vec<&str>>();
-    let width = lines[0].len();
-    let height = lines.len();
-
-    let mut index = 0;
-    while width * height > index {
-        let x = index % width;
-        let y = index / width;
-        let c = lines[y].chars().nth(x).unwrap();
-        match c {
-            '*' => {
-                let mut numbers: Vec<u32> = Vec::new();
-                let mut checked_indicies = Vec::new();
-
-                let x_range = || match x {
-                    0 => 0..1,
-                    s if s == width - 1 => x - 1..x,
-                    _ => x - 1..x + 1,
-                };
-
-                let y_range = || match y {
-                    0 => 0..1,
-                    s if s == height - 1 => y - 1..y,
-                    _ => y - 1..y + 1,
-                };
-
-                for x in x_range() {
-                    for y in y_range() {
-                        let is_numeric = lines[y].chars().nth(x).unwrap().is_numeric();
-                        let is_checked = checked_indicies.contains(&(x + y * width));
-                        if is_numeric && !is_checked
-                        {
-                            let mut left_offset = 0;
-                            let mut right_offset = 0;
-                            
-                            while x + right_offset + 1 < width {
-                                if let Some(c) = lines[y].chars().nth(x + right_offset + 1) {
-                                    if c.is_numeric() {
-                                        right_offset += 1;
-                                    } else {
-                                        break;
-                                    }
-                                } else {
-                                    break;
-                                }
-                            }
-
-                            while x - left_offset > 0 {
-                                if let Some(c) = lines[y].chars().nth(x - left_offset - 1) {
-                                    if c.is_numeric() {
-                                        left_offset += 1;
-                                    } else {
-                                        break;
-                                    }
-                                } else {
-                                    break;
-                                }
-                            }
-
-                            let number_string = lines[y]
-                                .chars()
-                                .skip(x - left_offset)
-                                .take(left_offset + right_offset + 1)
-                                .collect::<String>();
-
-                            let number = number_string
-                                .parse::<u32>()
-                                .unwrap();
-
-                            numbers.push(number);
-                            for i in x - left_offset..x + right_offset + 1 {
-                                checked_indicies.push(i + y * width);
-                            }
-                        }
-                    }
-                }
+fn solution_2(input: String) -> Result<u32, NumberTooLarge> {
+    let schematic = Schematic::parse(&input)?;
+    Ok(schematic
+        .gears(2)
+        .map(|gear| {
+            schematic
+                .numbers_of(gear)
+                .map(|number| number.value)
+                .product::<u32>()
+        })
+        .sum())
+}
 
-                if numbers.len() == 2 {
-                    solution += numbers.iter().product::<u32>();
-                }
+// answers one question about the schematic, one line per match
+fn query(schematic: &Schematic, question: &[String]) -> Option<Vec<String>> {
+    let describe = |number: &Number| format!("{} at {},{}", number.value, number.x, number.y);
+
+    let answer = match question {
+        [kind] if kind == "orphans" => schematic.orphan_numbers().map(describe).collect(),
+        [kind, symbol] if kind == "touching" && symbol.chars().count() == 1 => schematic
+            .numbers_touching(symbol.chars().next().unwrap())
+            .map(describe)
+            .collect(),
+        [kind, parts] if kind == "gears" => schematic
+            .gears(parts.parse().ok()?)
+            .map(|gear| {
+                let numbers = schematic
+                    .numbers_of(gear)
+                    .map(|number| number.value.to_string())
+                    .collect::<Vec<_>>();
+                format!("gear at {},{}: {}", gear.x, gear.y, numbers.join(" "))
+            })
+            .collect(),
+        _ => return None,
+    };
+    Some(answer)
+}
 
-                index += 1;
-            }
-            '\n' => (),
-            _ => {
-                index += 1;
-            }
-        }
+fn run_query(args: &[String]) {
+    let usage = "Usage: year_2023_day_03 query <orphans | touching <symbol> | gears <parts>> \
+                 [--input <path>]";
+    let (question, input_path) = match args {
+        [question @ .., flag, path] if flag == "--input" => (question, path.clone()),
+        question => (question, "inputs/2023/day3.txt".to_string()),
+    };
+
+    match std::fs::read_to_string(&input_path) {
+        Ok(input) => match Schematic::parse(&input) {
+            Ok(schematic) => match query(&schematic, question) {
+                Some(answer) => answer.iter().for_each(|line| println!("{}", line)),
+                None => println!("{}", usage),
+            },
+            Err(error) => println!("Error while reading {}: {}", input_path, error),
+        },
+        Err(err) => println!("Error while reading {}: {}", input_path, err),
     }
-
-    solution
 }
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("query") {
+        run_query(&args[2..]);
+        return;
+    }
+
     let mut aoc_day = AocDay::new(2023, 3);
-    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution_1(&x)).with_examples(&[
-        vec![".....", ".123.", "....."].join("\n"),
-        vec![".....", "*123.", "....."].join("\n"),
-        vec![".....", ".123*", "....."].join("\n"),
-        vec!["*....", ".123.", "....."].join("\n"),
-        vec![".*...", ".123.", "....."].join("\n"),
-        vec!["..*..", ".123.", "....."].join("\n"),
-        vec!["...*.", ".123.", "....."].join("\n"),
-        vec!["....*", ".123.", "....."].join("\n"),
-        vec![".....", ".123.", "*...."].join("\n"),
-        vec![".....", ".123.", ".*..."].join("\n"),
-        vec![".....", ".123.", "..*.."].join("\n"),
-        vec![".....", ".123.", "...*."].join("\n"),
-        vec![".....", ".123.", "....*"].join("\n"),
-        vec![".....", "1*23.", "....."].join("\n"),
-        vec![".....", "12*3.", "....."].join("\n"),
-        vec!["*....", ".123.", "....*"].join("\n"),
+    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| report(solution_1(&x))).with_examples(&[
+        [".....", ".123.", "....."].join("\n"),
+        [".....", "*123.", "....."].join("\n"),
+        [".....", ".123*", "....."].join("\n"),
+        ["*....", ".123.", "....."].join("\n"),
+        [".*...", ".123.", "....."].join("\n"),
+        ["..*..", ".123.", "....."].join("\n"),
+        ["...*.", ".123.", "....."].join("\n"),
+        ["....*", ".123.", "....."].join("\n"),
+        [".....", ".123.", "*...."].join("\n"),
+        [".....", ".123.", ".*..."].join("\n"),
+        [".....", ".123.", "..*.."].join("\n"),
+        [".....", ".123.", "...*."].join("\n"),
+        [".....", ".123.", "....*"].join("\n"),
+        [".....", "1*23.", "....."].join("\n"),
+        [".....", "12*3.", "....."].join("\n"),
+        ["*....", ".123.", "....*"].join("\n"),
     ]);
-    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution_2(x)).with_examples(&[
-        vec![".....", "11*32", "....."].join("\n"),
-        vec![".....", ".12*.", "....."].join("\n"),
-        vec![".....", ".123*", "...32"].join("\n"),
+    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| report(solution_2(x))).with_examples(&[
+        [".....", "11*32", "....."].join("\n"),
+        [".....", ".12*.", "....."].join("\n"),
+        [".....", ".123*", "...32"].join("\n"),
     ]);
 
     let _test_result_part_1 = aoc_day.test(&aoc_puzzle_part_1);
@@ -343,3 +285,126 @@ fn main() {
     let _test_result_part_2 = aoc_day.test(&aoc_puzzle_part_2);
     let _result_part_2 = aoc_day.run(&aoc_puzzle_part_2);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_input() -> String {
+        [
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ]
+        .join("\n")
+    }
+
+    fn values<'a>(numbers: impl Iterator<Item = &'a Number>) -> Vec<u32> {
+        numbers.map(|number| number.value).collect()
+    }
+
+    #[test]
+    fn parse_test() {
+        let schematic = Schematic::parse(&example_input()).unwrap();
+
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+        let star = &schematic.symbols[0];
+        assert_eq!((star.kind, star.x, star.y), ('*', 3, 1));
+        assert_eq!(values(schematic.numbers_of(star)), [467, 35]);
+        assert!(schematic.symbols_of(&schematic.numbers[0]).eq([star]));
+    }
+
+    #[test]
+    fn parse_too_large_test() {
+        let input = ["..12345678901.", ".*..........."].join("\n");
+
+        assert_eq!(
+            Schematic::parse(&input).err(),
+            Some(NumberTooLarge { x: 2, y: 0 })
+        );
+        assert!(Schematic::parse("4294967295*").is_ok());
+        assert_eq!(solution_1(&input), Err(NumberTooLarge { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn parse_multibyte_symbol_test() {
+        let schematic = Schematic::parse("..§\n.12\n§..").unwrap();
+
+        let symbols = schematic
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.kind, symbol.x, symbol.y));
+        assert_eq!(symbols.collect::<Vec<_>>(), [('§', 2, 0), ('§', 0, 2)]);
+        assert!(schematic
+            .symbols_of(&schematic.numbers[0])
+            .eq(&schematic.symbols));
+        assert_eq!(solution_1("§.\n.7"), Ok(7));
+    }
+
+    #[test]
+    fn gears_test() {
+        let schematic = Schematic::parse(&example_input()).unwrap();
+
+        let two = schematic.gears(2).map(|gear| (gear.x, gear.y));
+        assert_eq!(two.collect::<Vec<_>>(), [(3, 1), (5, 8)]);
+        let one = schematic.gears(1).map(|gear| (gear.x, gear.y));
+        assert_eq!(one.collect::<Vec<_>>(), [(3, 4)]);
+        assert_eq!(schematic.gears(3).count(), 0);
+    }
+
+    #[test]
+    fn numbers_touching_test() {
+        let schematic = Schematic::parse(&example_input()).unwrap();
+
+        assert_eq!(values(schematic.numbers_touching('#')), [633]);
+        assert_eq!(values(schematic.numbers_touching('$')), [664]);
+        assert_eq!(values(schematic.numbers_touching('%')), []);
+    }
+
+    #[test]
+    fn orphan_numbers_test() {
+        let schematic = Schematic::parse(&example_input()).unwrap();
+
+        assert_eq!(values(schematic.orphan_numbers()), [114, 58]);
+    }
+
+    #[test]
+    fn query_test() {
+        let schematic = Schematic::parse(&example_input()).unwrap();
+        let question = |words: &[&str]| {
+            query(
+                &schematic,
+                &words.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            question(&["orphans"]),
+            Some(vec!["114 at 5,0".to_string(), "58 at 7,5".to_string()])
+        );
+        assert_eq!(question(&["gears", "2"]).unwrap()[0], "gear at 3,1: 467 35");
+        assert_eq!(question(&["touching", "$"]).unwrap().len(), 1);
+        assert_eq!(question(&["gears", "x"]), None);
+    }
+
+    #[test]
+    fn solution_1_test() {
+        assert_eq!(solution_1(&example_input()), Ok(4361));
+        assert_eq!(solution_1(&[".....", "12*3.", "....."].join("\n")), Ok(15));
+    }
+
+    #[test]
+    fn solution_2_test() {
+        assert_eq!(solution_2(example_input()), Ok(467835));
+        assert_eq!(solution_2([".....", "11*32", "....."].join("\n")), Ok(352));
+        assert_eq!(solution_2([".....", ".123*", "...32"].join("\n")), Ok(3936));
+    }
+}
@@ -1,14 +1,15 @@
+use advent_of_code::byte_parsing::{self, lines, skip_whitespace, split_once, tag, unsigned_list};
+use advent_of_code::parsing::{parse_records, report, AocParse, Diagnostic};
 use aoc_helper::{AocDay, Puzzle};
-use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, space0, space1},
-    multi::*,
-    sequence::{delimited, terminated, tuple},
-    *,
-};
+use std::fmt;
 
+const INPUT_PATH: &str = "inputs/2023/day4.txt";
+
+#[derive(AocParse)]
+#[aoc(prefix = "Card", sep = "|")]
 struct Card {
     id: u32,
+    #[aoc(before = ":")]
     winning_numbers: Vec<u32>,
     scratch_numbers: Vec<u32>,
 }
@@ -51,92 +52,250 @@ impl Card {
     }
 }
 
-fn parse_numbers(input: &str) -> IResult<&str, Vec<u32>> {
-    fold_many1(
-        terminated(nom::character::complete::u32, space0),
-        Vec::new,
-        |mut acc: Vec<u32>, item| {
-            acc.push(item);
-            acc
-        },
-    )(input)
-}
+// fast path for well formed cards, `None` on anything the derived parser would have to explain
+fn parse_card_bytes(input: &[u8]) -> Option<Card> {
+    let input = tag(input, b"Card")?;
+    let (id, input) = byte_parsing::unsigned(skip_whitespace(input))?;
+    let input = tag(input, b":")?;
+    let (winning, scratch) = split_once(input, b'|')?;
+
+    let mut winning = unsigned_list(winning);
+    let winning_numbers: Vec<u32> = winning.by_ref().collect();
+    let mut scratch = unsigned_list(scratch);
+    let scratch_numbers: Vec<u32> = scratch.by_ref().collect();
+
+    let complete = skip_whitespace(winning.rest()).is_empty()
+        && skip_whitespace(scratch.rest()).is_empty()
+        && !winning_numbers.is_empty()
+        && !scratch_numbers.is_empty();
 
-fn parse_card(input: &str) -> IResult<&str, Card> {
-    let (input, card_id) = delimited(
-        tuple((tag("Card"), space1)),
-        digit1,
-        tuple((nom::character::complete::char(':'), space1)))
-    (input)?;
-
-    let (input, (winning_numbers, _, scratch_numbers)) = tuple((
-        delimited(space0, parse_numbers, space0),
-        character::complete::char('|'),
-        delimited(space0, parse_numbers, space0)
-    ))(input)?;
-
-    Ok((input, Card {
-        id: card_id.parse().unwrap(),
+    complete.then_some(Card {
+        id,
         winning_numbers,
         scratch_numbers,
-    }))
+    })
 }
 
-fn solution_1(input: &str) -> u32 {
-    let mut solution = 0;
-
-    for line in input.lines() {
-        let (_, card) = parse_card(line).unwrap();
-        solution += card.score();
+// `source` names where the input came from in diagnostics
+fn parse_cards(input: &str, source: &str) -> Result<Vec<Card>, Diagnostic> {
+    match lines(input.as_bytes()).map(parse_card_bytes).collect() {
+        Some(cards) => Ok(cards),
+        None => parse_records(source, input),
     }
+}
 
-    return solution;
+#[derive(Debug, PartialEq)]
+enum CascadeError {
+    Parse(Diagnostic),
+    /// The copies of card `card` no longer fit into a `u128`.
+    Overflow {
+        card: u32,
+    },
 }
 
-fn solution_2(input: &str) -> u32 {
-    // guess this is big enough?
-    let mut copies: [u32; 1000] = [1; 1000];
+impl fmt::Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CascadeError::Parse(diagnostic) => write!(f, "{}", diagnostic),
+            CascadeError::Overflow { card } => write!(f, "too many copies of card {}", card),
+        }
+    }
+}
+
+impl std::error::Error for CascadeError {}
 
-    for line in input.lines() {
-        let (_, card) = parse_card(line).unwrap();
+impl From<Diagnostic> for CascadeError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        CascadeError::Parse(diagnostic)
+    }
+}
 
-        let amount = card.win_amount();
-        let card_copies = copies[card.id as usize];
+// copies of every card in input order, wins past the last card copy nothing
+fn card_copies(cards: &[Card]) -> Result<Vec<u128>, CascadeError> {
+    let mut copies = vec![1u128; cards.len()];
 
-        for i in 0..amount {
-            copies[(card.id + i + 1) as usize] += card_copies;
+    for (index, card) in cards.iter().enumerate() {
+        let won = (index + 1..cards.len()).take(card.win_amount() as usize);
+        for next in won {
+            let overflow = CascadeError::Overflow {
+                card: cards[next].id,
+            };
+            copies[next] = copies[next].checked_add(copies[index]).ok_or(overflow)?;
         }
     }
 
-    let last_card_id = input.lines().count();
-    let solution = copies.iter().skip(1).take(last_card_id).sum();
+    Ok(copies)
+}
+
+fn solution_1(input: &str, source: &str) -> Result<u32, Diagnostic> {
+    let cards = parse_cards(input, source)?;
+
+    Ok(cards.iter().map(Card::score).sum())
+}
+
+fn solution_2(input: &str, source: &str) -> Result<u128, CascadeError> {
+    let cards = parse_cards(input, source)?;
 
-    return solution;
+    card_copies(&cards)?
+        .into_iter()
+        .zip(&cards)
+        .try_fold(0u128, |total, (copies, card)| {
+            total
+                .checked_add(copies)
+                .ok_or(CascadeError::Overflow { card: card.id })
+        })
+}
+
+fn run_copies(args: &[String]) {
+    let input_path = match args {
+        [] => INPUT_PATH,
+        [path] => path.as_str(),
+        _ => {
+            println!("Usage: year_2023_day_04 copies [path]");
+            return;
+        }
+    };
+
+    let input = match std::fs::read_to_string(input_path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Error while reading {}: {}", input_path, err);
+            return;
+        }
+    };
+
+    let copies = parse_cards(&input, input_path)
+        .map_err(CascadeError::from)
+        .and_then(|cards| Ok((card_copies(&cards)?, cards)));
+    match copies {
+        Ok((copies, cards)) => {
+            for (card, copies) in cards.iter().zip(copies) {
+                println!("Card {}: {}", card.id, copies);
+            }
+        }
+        Err(error) => println!("{}", error),
+    }
 }
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("copies") {
+        run_copies(&args[2..]);
+        return;
+    }
+
     let mut aoc_day = AocDay::new(2023, 4);
-    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution_1(&x)).with_examples(&[vec![
-        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
-        "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
-        "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
-        "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
-        "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
-        "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-    ]
-    .join("\n")]);
-    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution_2(&x)).with_examples(&[vec![
-        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
-        "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
-        "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
-        "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
-        "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
-        "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-    ]
-    .join("\n")]);
-    let _test_result_part_1 = aoc_day.test(&aoc_puzzle_part_1);
+    aoc_day.input(INPUT_PATH);
+    let example_part_1 = Puzzle::new(1, |x: String| report(solution_1(&x, "example")))
+        .with_examples(&[vec![
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]
+        .join("\n")]);
+    let example_part_2 = Puzzle::new(2, |x: String| report(solution_2(&x, "example")))
+        .with_examples(&[vec![
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]
+        .join("\n")]);
+    // separate puzzles for the real input, so diagnostics name the text they point into
+    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| report(solution_1(&x, INPUT_PATH)));
+    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| report(solution_2(&x, INPUT_PATH)));
+
+    let _test_result_part_1 = aoc_day.test(&example_part_1);
     let _result_part_1 = aoc_day.run(&aoc_puzzle_part_1);
 
-    let _test_result_part_2 = aoc_day.test(&aoc_puzzle_part_2);
+    let _test_result_part_2 = aoc_day.test(&example_part_2);
     let _result_part_2 = aoc_day.run(&aoc_puzzle_part_2);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_input() -> String {
+        vec![
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn solution_test() {
+        assert_eq!(solution_1(&example_input(), "example"), Ok(13));
+        assert_eq!(solution_2(&example_input(), "example"), Ok(30));
+    }
+
+    #[test]
+    fn card_copies_test() {
+        let cards = parse_cards(&example_input(), "example").unwrap();
+        assert_eq!(card_copies(&cards), Ok(vec![1, 2, 4, 8, 14, 1]));
+
+        // the last card's wins run off the end of the table
+        let input = "Card 1: 1 2 | 1 2\nCard 2: 3 4 | 3 4";
+        assert_eq!(solution_2(input, "example"), Ok(3));
+    }
+
+    #[test]
+    fn long_cascade_test() {
+        let card = |id, wins: u32| Card {
+            id,
+            winning_numbers: (1..=wins).collect(),
+            scratch_numbers: (1..=wins).collect(),
+        };
+
+        let cards = (1..=1500).map(|id| card(id, 1)).collect::<Vec<_>>();
+        assert_eq!(card_copies(&cards).unwrap()[1499], 1500);
+
+        // every card copies the next two, so the copies grow like the Fibonacci numbers
+        let cards = (1..=200).map(|id| card(id, 2)).collect::<Vec<_>>();
+        assert_eq!(
+            card_copies(&cards),
+            Err(CascadeError::Overflow { card: 185 })
+        );
+    }
+
+    #[test]
+    fn parse_card_bytes_test() {
+        for line in example_input().lines() {
+            let fast = parse_card_bytes(line.as_bytes()).unwrap();
+            let card = line.parse::<Card>().unwrap();
+
+            assert_eq!(fast.id, card.id);
+            assert_eq!(fast.winning_numbers, card.winning_numbers);
+            assert_eq!(fast.scratch_numbers, card.scratch_numbers);
+        }
+
+        assert!(parse_card_bytes(b"Card 1: 41 48 / 83").is_none());
+        assert!(parse_card_bytes(b"Card 1: 41 48 | 83 x").is_none());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let input = example_input().replace("Card 3:  1 21 53 59 44 |", "Card 3:  1 21 53 59 44 /");
+
+        let diagnostic = solution_1(&input, "cards.txt").unwrap_err();
+        assert_eq!(diagnostic.file, "cards.txt");
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 24));
+        assert_eq!(diagnostic.expected, "`|`");
+
+        let diagnostic = solution_1("Card x: 1 | 2", "example").unwrap_err();
+        assert_eq!(
+            (diagnostic.column, diagnostic.expected.as_str()),
+            (6, "u32")
+        );
+    }
+}
@@ -1,98 +1,23 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::vec;
 
+use advent_of_code::interval::{Interval, IntervalSet};
+use advent_of_code::parsing::report;
+use advent_of_code::range_map::RangeMap;
 use aoc_helper::{AocDay, Puzzle};
 
-#[derive(Clone, Copy)]
-struct Interval {
-    start: u64,
-    width: u64,
-}
-
-impl Interval {
-    fn new(start: u64, width: u64) -> Self {
-        Interval { start, width }
-    }
-
-    fn overlaps(&self, other: &Self) -> bool {
-        self.start < other.start + other.width && other.start < self.start + self.width
-    }
-
-    fn intersect(&self, other: &Self) -> Option<Self> {
-        if self.overlaps(other) {
-            let start = self.start.max(other.start);
-            let end = (self.start + self.width).min(other.start + other.width);
-            Some(Interval::new(start, end - start))
-        } else {
-            None
-        }
-    }
-
-    
-}
-
-
-// struct IntervalTree<T: Copy> {
-//     interval: Interval<T>,
-//     max: u64,
-//     left: Option<Box<IntervalTree<T>>>,
-//     right: Option<Box<IntervalTree<T>>>,
-// }
-
-// impl<T: Copy> IntervalTree<T> {
-//     fn new(interval: Interval<T>) -> Self {
-//         IntervalTree {
-//             interval,
-//             max: interval.end,
-//             left: None,
-//             right: None,
-//         }
-//     }
-
-//     fn insert(&mut self, interval: Interval<T>) {
-//         if self.interval.start > interval.start {
-//             if let Some(left_node) = &mut self.left {
-//                 left_node.insert(interval);
-//             } else {
-//                 self.left = Some(Box::new(IntervalTree::new(interval)));
-//             }
-//         } else {
-//             if let Some(right_node) = &mut self.right {
-//                 right_node.insert(interval);
-//             } else {
-//                 self.right = Some(Box::new(IntervalTree::new(interval)));
-//             }
-//         }
-
-//         self.max = self.max.max(interval.end);
-//     }
-
-//     fn find_value(&self, value: u64) -> Option<&Interval<T>> {
-//         if self.interval.start < value && value <= self.interval.end {
-//             return Some(&self.interval);
-//         }
-
-//         if let Some(left_node) = &self.left {
-//             return left_node.find_value(value);
-//         }
-
-//         if let Some(right_node) = &self.right {
-//             if right_node.max > value {
-//                 return right_node.find_value(value);
-//             }
-//         }
-
-//         None
-//     }
-// }
-
-fn parse_seeds(input: &str) -> Vec<u64> {
-    let mut seeds = Vec::new();
-    let first_line = input.lines().next().unwrap();
-    let first_line = first_line.split(":").nth(1).unwrap();
-    for seed in first_line.split_whitespace().filter(|x| !x.is_empty()) {
-        seeds.push(seed.parse::<u64>().unwrap());
-    }
-    seeds
+fn parse_seeds(input: &str) -> Result<Vec<u64>, AlmanacError> {
+    let first_line = input.lines().next().unwrap_or("");
+    first_line
+        .split_once(':')
+        .and_then(|(_, seeds)| {
+            seeds
+                .split_whitespace()
+                .map(|seed| seed.parse::<u64>().ok())
+                .collect()
+        })
+        .ok_or_else(|| AlmanacError::InvalidSeeds(first_line.to_string()))
 }
 
 // fn parse_seed_ranges(input: &str) -> Vec<std::ops::Range<u64>> {
@@ -107,16 +32,15 @@ fn parse_seeds(input: &str) -> Vec<u64> {
 //     seeds
 // }
 
-fn parse_seed_intervals(input: &str) -> Vec<Interval> {
-    let mut seeds = Vec::new();
-    let first_line = input.lines().next().unwrap();
-    let first_line = first_line.split(":").nth(1).unwrap();
-    for seed in first_line.split_whitespace().filter(|x| !x.is_empty()) {
-        let start_index = seed.parse::<u64>().unwrap();
-        let width = seed.parse::<u64>().unwrap();
-        seeds.push(Interval::new(start_index, width - start_index));
+fn parse_seed_intervals(input: &str) -> Result<Vec<Interval>, AlmanacError> {
+    let seeds = parse_seeds(input)?;
+    let pairs = seeds.chunks_exact(2);
+
+    if let [seed] = pairs.remainder() {
+        return Err(AlmanacError::UnpairedSeed(*seed));
     }
-    seeds
+
+    Ok(pairs.map(|pair| Interval::new(pair[0], pair[1])).collect())
 }
 
 // fn parse_map(input: String) -> Vec<(u64, u64, u64)> {
@@ -133,48 +57,175 @@ fn parse_seed_intervals(input: &str) -> Vec<Interval> {
 //     map
 // }
 
-// fn parse_map(input: String) -> IntervalTree<u64> {
-//     let mut interval_tree: IntervalTree<u64> = IntervalTree::new(Interval::new(0, 0, 0));
-//     let mut lines = input.lines();
-//     lines.next();
-//     for line in lines {
-//         let mut parts = line.split_whitespace().filter(|x| !x.is_empty());
-//         let target = parts.next().unwrap().parse::<u64>().unwrap();
-//         let source = parts.next().unwrap().parse::<u64>().unwrap();
-//         let width = parts.next().unwrap().parse::<u64>().unwrap();
-//         interval_tree.insert(Interval::new(source, source + width, target));
-//     }
-//     interval_tree
-// }
+// a `<target> <source> <width>` line
+fn parse_rule(line: &str) -> Option<(Interval, Interval)> {
+    let numbers = line
+        .split_whitespace()
+        .map(|x| x.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match numbers[..] {
+        [target, source, width] => {
+            Some((Interval::new(source, width), Interval::new(target, width)))
+        }
+        _ => None,
+    }
+}
 
-// fn parse_maps(input: &str) -> Vec<IntervalTree<u64>> {
-//     let mut maps = Vec::new();
-//     for map in input.split("\n\n") {
-//         maps.push(parse_map(map.to_string()));
-//     }
-//     maps
-// }
+// the lines of one map, starting with its header
+fn parse_map_interval<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<(Interval, Interval)>, AlmanacError> {
+    lines
+        .into_iter()
+        .skip(1)
+        .map(|line| {
+            parse_rule(line).ok_or_else(|| AlmanacError::InvalidRule {
+                line: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn to_range_map(map: Vec<(Interval, Interval)>) -> RangeMap {
+    RangeMap::from_rules(
+        map.into_iter()
+            .map(|(source, target)| (source, target.start)),
+    )
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum AlmanacError {
+    InvalidSeeds(String),
+    InvalidHeader(String),
+    UnpairedSeed(u64),
+    InvalidRule { line: String },
+    DuplicateMap { source: String, target: String },
+    NoConversion { source: String, target: String },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::InvalidSeeds(line) => {
+                write!(f, "expected a `seeds: <number>...` line, got `{}`", line)
+            }
+            AlmanacError::InvalidHeader(line) => {
+                write!(
+                    f,
+                    "expected a `<source>-to-<target> map:` header, got `{}`",
+                    line
+                )
+            }
+            AlmanacError::UnpairedSeed(seed) => {
+                write!(f, "seed range starting at {} has no length", seed)
+            }
+            AlmanacError::InvalidRule { line } => {
+                write!(
+                    f,
+                    "expected a `<target> <source> <length>` rule, got `{}`",
+                    line
+                )
+            }
+            AlmanacError::DuplicateMap { source, target } => {
+                write!(
+                    f,
+                    "the {} to {} map is defined more than once",
+                    source, target
+                )
+            }
+            AlmanacError::NoConversion { source, target } => {
+                write!(f, "no chain of maps converts {} to {}", source, target)
+            }
+        }
+    }
+}
+
+struct Conversion {
+    source: String,
+    target: String,
+    map: RangeMap,
+}
+
+struct Almanac {
+    conversions: Vec<Conversion>,
+}
+
+impl Almanac {
+    // breadth first, so the shortest chain of maps is used
+    fn conversion(&self, source: &str, target: &str) -> Result<RangeMap, AlmanacError> {
+        let mut reached_by: HashMap<&str, usize> = HashMap::new();
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == target {
+                let mut path = Vec::new();
+                let mut current = target;
+                while current != source {
+                    let index = reached_by[current];
+                    path.push(index);
+                    current = &self.conversions[index].source;
+                }
+
+                return Ok(path.iter().rev().fold(RangeMap::new(), |map, &index| {
+                    map.compose(&self.conversions[index].map)
+                }));
+            }
+
+            for (index, conversion) in self.conversions.iter().enumerate() {
+                let next = conversion.target.as_str();
+                if conversion.source == category && next != source && !reached_by.contains_key(next)
+                {
+                    reached_by.insert(next, index);
+                    queue.push_back(next);
+                }
+            }
+        }
 
-fn parse_map_interval(input: String) -> Vec<(Interval, Interval)> {
-    let mut map = Vec::new();
-    let mut lines = input.lines();
-    lines.next();
-    for line in lines {
-        let mut parts = line.split_whitespace().filter(|x| !x.is_empty());
-        let target = parts.next().unwrap().parse::<u64>().unwrap();
-        let source = parts.next().unwrap().parse::<u64>().unwrap();
-        let width = parts.next().unwrap().parse::<u64>().unwrap();
-        map.push((Interval::new(source, width), Interval::new(target, width)));
+        Err(AlmanacError::NoConversion {
+            source: source.to_string(),
+            target: target.to_string(),
+        })
     }
-    map
 }
 
-fn parse_map_intervals(input: &str) -> Vec<Vec<(Interval, Interval)>> {
-    let mut maps = Vec::new();
-    for map in input.split("\n\n") {
-        maps.push(parse_map_interval(map.to_string()));
+fn parse_map_header(line: &str) -> Result<(String, String), AlmanacError> {
+    line.strip_suffix(" map:")
+        .and_then(|name| name.split_once("-to-"))
+        .map(|(source, target)| (source.to_string(), target.to_string()))
+        .ok_or_else(|| AlmanacError::InvalidHeader(line.to_string()))
+}
+
+fn parse_almanac(input: &str) -> Result<Almanac, AlmanacError> {
+    let mut conversions: Vec<Conversion> = Vec::new();
+
+    // blocks are separated by blank lines, the seeds before the first one are skipped
+    let mut blocks: Vec<Vec<&str>> = Vec::new();
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            blocks.push(Vec::new());
+        } else if let Some(block) = blocks.last_mut() {
+            block.push(line);
+        }
+    }
+
+    for block in blocks.into_iter().filter(|block| !block.is_empty()) {
+        let (source, target) = parse_map_header(block[0])?;
+
+        if conversions
+            .iter()
+            .any(|conversion| conversion.source == source && conversion.target == target)
+        {
+            return Err(AlmanacError::DuplicateMap { source, target });
+        }
+
+        conversions.push(Conversion {
+            source,
+            target,
+            map: to_range_map(parse_map_interval(block)?),
+        });
     }
-    maps
+
+    Ok(Almanac { conversions })
 }
 
 // fn solution_1(input: &str) -> u64 {
@@ -196,23 +247,14 @@ fn parse_map_intervals(input: &str) -> Vec<Vec<(Interval, Interval)>> {
 
 // }
 
-fn solution_1(input: &str) -> u64 {
-    // let mut seeds = parse_seeds(input);
-    // let maps = parse_maps(input);
-
-    // let mut minimum_land = u64::max_value();
-    // for seed in seeds {
-    //     let mut result = seed;
-    //     for map in &maps {
-    //         if let Some(interval) = map.find_value(result) {
-    //             result = interval.value + result - interval.start;
-    //         }
-    //     }
-    //     minimum_land = minimum_land.min(result);
-    // }
-
-    // minimum_land
-    0
+fn solution_1(input: &str) -> Result<u64, AlmanacError> {
+    let almanac = parse_almanac(input)?.conversion("seed", "location")?;
+
+    Ok(parse_seeds(input)?
+        .into_iter()
+        .map(|seed| almanac.apply(seed))
+        .min()
+        .unwrap_or(u64::MAX))
 }
 
 // fn solution_2(input: &str) -> u64 {
@@ -242,66 +284,74 @@ fn solution_1(input: &str) -> u64 {
 //     minimum_land
 // }
 
-fn solution_2(input: &str) -> u64 {
-    // let seed_ranges = parse_seed_ranges(input);
-    // let maps = parse_maps(input);
-
-    let seed_intervals: Vec<Interval> = parse_seed_intervals(input);
-    let interval_maps: Vec<Vec<(Interval, Interval)>> = parse_map_intervals(input);
-
-    let mut minimum_land = u64::max_value();
-    for seed_interval in seed_intervals {
-        let mut source_intervals = vec![seed_interval];
-        let mut target_intervals = Vec::new();
-
-        for map in &interval_maps {
-            for (map_source_interval, map_target_interval) in map {
-                // map source intervals to target intervals
-                for source_interval in &source_intervals {
-                    if let Some(intersect) = source_interval.intersect(&map_source_interval) {
-                        target_intervals.push(Interval::new(
-                            map_target_interval.start + intersect.start - map_source_interval.start,
-                            intersect.width,
-                        ));
-                    }
-                }
+fn solution_2(input: &str) -> Result<u64, AlmanacError> {
+    let seeds: IntervalSet = parse_seed_intervals(input)?.into_iter().collect();
+    let almanac = parse_almanac(input)?.conversion("seed", "location")?;
 
-                source_intervals = target_intervals;
-                target_intervals = Vec::new();
-            }
+    Ok(almanac.apply_set(&seeds).min().unwrap_or(u64::MAX))
+}
 
-            source_intervals = target_intervals;
-            target_intervals = Vec::new();
-        }
+// lists the seed ranges that end up in `locations`, for debugging the almanac
+fn inverse_report(input: &str, locations: Interval) -> Result<String, AlmanacError> {
+    let almanac = parse_almanac(input)?.conversion("seed", "location")?;
+    let locations: IntervalSet = [locations].into_iter().collect();
+    let seeds = almanac.preimage(&locations);
+    let listed_seeds: IntervalSet = parse_seed_intervals(input)?.into_iter().collect();
+
+    let format_set = |set: &IntervalSet| {
+        set.intervals()
+            .iter()
+            .map(|interval| interval.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    Ok(format!(
+        "locations {}\n  all seeds: {}\n  listed seeds: {}",
+        format_set(&locations),
+        format_set(&seeds),
+        format_set(&seeds.intersection(&listed_seeds))
+    ))
+}
 
-        for source_interval in source_intervals {
-            minimum_land = minimum_land.min(source_interval.start);
+fn run_inverse(args: &[String]) {
+    let bounds = (
+        args.first().and_then(|x| x.parse::<u64>().ok()),
+        args.get(1).and_then(|x| x.parse::<u64>().ok()),
+    );
+
+    let (start, end) = match bounds {
+        (Some(start), Some(end)) if start < end => (start, end),
+        _ => {
+            println!("Usage: year_2023_day_05 inverse <start> <end> [input path]");
+            return;
         }
+    };
+
+    let input_path = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| "inputs/2023/day5.txt".to_string());
+
+    match std::fs::read_to_string(&input_path) {
+        Ok(input) => match inverse_report(&input, Interval::from_bounds(start, end)) {
+            Ok(report) => println!("{}", report),
+            Err(err) => println!("Error in almanac {}: {}", input_path, err),
+        },
+        Err(err) => println!("Error while reading {}: {}", input_path, err),
     }
-
-    // let mut range_count = 0;
-    // let mut minimum_land = u64::max_value();
-    // for seed_range in seed_ranges {
-    //     for seed in seed_range {
-    //         let mut result = seed;
-    //         for map in &maps {
-    //             if let Some(interval) = map.find_value(result) {
-    //                 result = interval.value + result - interval.start;
-    //             }
-    //         }
-    //         minimum_land = minimum_land.min(result);
-    //     }
-    //     range_count += 1;
-    //     println!("{}", range_count);
-    // }
-
-    minimum_land
 }
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("inverse") {
+        run_inverse(&args[2..]);
+        return;
+    }
+
     let mut aoc_day = AocDay::new(2023, 5);
-    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution_1(&x));
-    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution_2(&x));
+    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| report(solution_1(&x)));
+    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| report(solution_2(&x)));
     let _result_part_1 = aoc_day.run(&aoc_puzzle_part_1);
     let _result_part_2 = aoc_day.run(&aoc_puzzle_part_2);
 }
@@ -310,35 +360,89 @@ fn main() {
 mod tests {
     use super::*;
 
+    fn example_input() -> String {
+        vec![
+            "seeds: 79 14 55 13",
+            "",
+            "seed-to-soil map:",
+            "50 98 2",
+            "52 50 48",
+            "",
+            "soil-to-fertilizer map:",
+            "0 15 37",
+            "37 52 2",
+            "39 0 15",
+            "",
+            "fertilizer-to-water map:",
+            "49 53 8",
+            "0 11 42",
+            "42 0 7",
+            "57 7 4",
+            "",
+            "water-to-light map:",
+            "88 18 7",
+            "18 25 70",
+            "",
+            "light-to-temperature map:",
+            "45 77 23",
+            "81 45 19",
+            "68 64 13",
+            "",
+            "temperature-to-humidity map:",
+            "0 69 1",
+            "1 0 69",
+            "",
+            "humidity-to-location map:",
+            "60 56 37",
+            "56 93 4",
+        ]
+        .join("\n")
+    }
+
     #[test]
     fn parse_seeds_test() {
         assert!(
-            parse_seeds("seeds: 79 14 55 13").eq(&vec![79, 14, 55, 13]),
+            parse_seeds("seeds: 79 14 55 13")
+                .unwrap()
+                .eq(&vec![79, 14, 55, 13]),
             "parse_seeds_test failed"
         );
+        assert_eq!(
+            parse_seeds("seeds: 79 x"),
+            Err(AlmanacError::InvalidSeeds("seeds: 79 x".to_string()))
+        );
     }
 
     #[test]
-    fn parse_seed_ranges_test() {
+    fn parse_seed_intervals_test() {
+        assert_eq!(
+            parse_seed_intervals("seeds: 79 14 55 13"),
+            Ok(vec![Interval::new(79, 14), Interval::new(55, 13)])
+        );
         assert_eq!(
-            parse_seed_ranges("seeds: 79 14 55 13"),
-            vec![79..93, 55..69]
-        )
+            parse_seed_intervals("seeds: 79 14 55"),
+            Err(AlmanacError::UnpairedSeed(55))
+        );
     }
 
     #[test]
     fn parse_map_test() {
-        let input = vec!["seed-to-soil map:", "50 98 2", "52 50 48"].join("\n");
+        let input = ["seed-to-soil map:", "50 98 2", "52 50 48"];
 
-        assert!(
-            parse_map(input).eq(&vec![(50..52, 98..100), (52..100, 50..98)]),
-            "parse_map_test failed"
+        assert_eq!(
+            parse_map_interval(input),
+            Ok(vec![
+                (Interval::new(98, 2), Interval::new(50, 2)),
+                (Interval::new(50, 48), Interval::new(52, 48))
+            ])
         );
     }
 
     #[test]
-    fn parse_maps_test() {
-        let input = vec![
+    fn parse_almanac_test() {
+        let input = [
+            "seeds: 79",
+            "",
             "seed-to-soil map:",
             "50 98 2",
             "52 50 48",
@@ -349,12 +453,106 @@ mod tests {
         ]
         .join("\n");
 
-        assert!(
-            parse_maps(&input).eq(&vec![
-                vec![(50..52, 98..100), (52..100, 50..98)],
-                vec![(50..52, 98..100), (52..100, 50..98)]
-            ]),
-            "parse_maps_test failed"
+        let almanac = parse_almanac(&input).unwrap();
+        let categories = almanac
+            .conversions
+            .iter()
+            .map(|conversion| (conversion.source.as_str(), conversion.target.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(categories, vec![("seed", "soil"), ("soil", "water")]);
+        assert_eq!(almanac.conversion("seed", "water").unwrap().apply(98), 52);
+    }
+
+    #[test]
+    fn parse_almanac_errors_test() {
+        let duplicate = [
+            "seeds: 79",
+            "",
+            "seed-to-soil map:",
+            "1 2 3",
+            "",
+            "seed-to-soil map:",
+        ]
+        .join("\n");
+        let invalid = ["seeds: 79", "", "seed to soil:", "1 2 3"].join("\n");
+        let short_rule = ["seeds: 79", "", "seed-to-soil map:", "1 2"].join("\n");
+        let bad_number = ["seeds: 79", "", "seed-to-soil map:", "1 x 3"].join("\n");
+
+        assert_eq!(
+            parse_almanac(&duplicate).err(),
+            Some(AlmanacError::DuplicateMap {
+                source: "seed".to_string(),
+                target: "soil".to_string()
+            })
+        );
+        assert_eq!(
+            parse_almanac(&invalid).err(),
+            Some(AlmanacError::InvalidHeader("seed to soil:".to_string()))
+        );
+        assert_eq!(
+            parse_almanac(&short_rule).err(),
+            Some(AlmanacError::InvalidRule {
+                line: "1 2".to_string()
+            })
+        );
+        assert_eq!(
+            parse_almanac(&bad_number).err(),
+            Some(AlmanacError::InvalidRule {
+                line: "1 x 3".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn crlf_input_test() {
+        let input = example_input().replace('\n', "\r\n");
+
+        assert_eq!(solution_1(&input), Ok(35));
+        assert_eq!(solution_2(&input), Ok(46));
+    }
+
+    #[test]
+    fn conversion_test() {
+        let almanac = parse_almanac(&example_input()).unwrap();
+
+        assert_eq!(
+            almanac.conversion("soil", "humidity").unwrap().apply(81),
+            78
+        );
+        assert_eq!(almanac.conversion("seed", "seed").unwrap(), RangeMap::new());
+        assert_eq!(
+            almanac.conversion("location", "seed").err(),
+            Some(AlmanacError::NoConversion {
+                source: "location".to_string(),
+                target: "seed".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn to_range_map_keeps_unmapped_values_test() {
+        let map = to_range_map(parse_map_interval(["seed-to-soil map:", "50 98 2"]).unwrap());
+        let seeds: IntervalSet = [Interval::new(90, 10)].into_iter().collect();
+
+        assert_eq!(
+            map.apply_set(&seeds),
+            [Interval::new(50, 2), Interval::new(90, 8)]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn inverse_report_test() {
+        assert_eq!(
+            inverse_report(&example_input(), Interval::new(46, 1)).unwrap(),
+            vec![
+                "locations 46..47",
+                "  all seeds: 82..83",
+                "  listed seeds: 82..83"
+            ]
+            .join("\n")
         );
     }
 
@@ -397,6 +595,48 @@ mod tests {
         ]
         .join("\n");
 
-        assert_eq!(solution_1(&input), 35, "solution_1_test failed");
+        assert_eq!(solution_1(&input), Ok(35), "solution_1_test failed");
+    }
+
+    #[test]
+    fn solution_2_test() {
+        let input = vec![
+            "seeds: 79 14 55 13",
+            "",
+            "seed-to-soil map:",
+            "50 98 2",
+            "52 50 48",
+            "",
+            "soil-to-fertilizer map:",
+            "0 15 37",
+            "37 52 2",
+            "39 0 15",
+            "",
+            "fertilizer-to-water map:",
+            "49 53 8",
+            "0 11 42",
+            "42 0 7",
+            "57 7 4",
+            "",
+            "water-to-light map:",
+            "88 18 7",
+            "18 25 70",
+            "",
+            "light-to-temperature map:",
+            "45 77 23",
+            "81 45 19",
+            "68 64 13",
+            "",
+            "temperature-to-humidity map:",
+            "0 69 1",
+            "1 0 69",
+            "",
+            "humidity-to-location map:",
+            "60 56 37",
+            "56 93 4",
+        ]
+        .join("\n");
+
+        assert_eq!(solution_2(&input), Ok(46), "solution_2_test failed");
     }
 }
@@ -1,46 +1,19 @@
+use advent_of_code::table::{Table, TableError};
 use aoc_helper::{AocDay, Puzzle};
 
-fn parse_race(input: &str) -> Vec<(u32, u32)> {
-    let mut lines = input.lines();
+const ROWS: [&str; 2] = ["Time", "Distance"];
 
-    let first_line = lines.next().unwrap();
-    let second_line = lines.next().unwrap();
-
-    let mut first_line_iter = first_line[5..].split_whitespace().filter(|x| !x.is_empty());
-    let mut second_line_iter = second_line[9..]
-        .split_whitespace()
-        .filter(|x| !x.is_empty());
-
-    //zip
-    let result =
-        first_line_iter
-            .zip(second_line_iter)
-            .fold(Vec::new(), |mut acc, (time, distance)| {
-                acc.push((
-                    time.parse::<u32>().unwrap(),
-                    distance.parse::<u32>().unwrap(),
-                ));
-                acc
-            });
-
-    result
+fn parse_race(input: &str) -> Result<Vec<(u32, u32)>, TableError> {
+    Table::parse(input)?.columns(&ROWS)
 }
 
-fn parse_race_part_2(input: &str) -> (u64, u64) {
-    let mut lines = input.lines();
-
-    let first_line = lines.next().unwrap();
-    let second_line = lines.next().unwrap();
-
-    let time = first_line[5..].split(" ").filter(|x| !x.is_empty()).collect::<String>().parse::<u64>().unwrap();
-    let distance = second_line[9..].split(" ").filter(|x| !x.is_empty()).collect::<String>().parse::<u64>().unwrap();
-
-    (time, distance)
+fn parse_race_part_2(input: &str) -> Result<(u64, u64), TableError> {
+    Table::parse(input)?.concatenated(&ROWS)
 }
 
 fn solution_1(input: &str) -> u32 {
     let mut solution = 1;
-    let races = parse_race(input);
+    let races = parse_race(input).unwrap();
 
     for (time, record) in races {
         let criterion = (time * time - 4 * record) as f64;
@@ -58,7 +31,7 @@ fn solution_1(input: &str) -> u32 {
 }
 
 fn solution_2(input: &str) -> u64 {
-    let (time, record) = parse_race_part_2(input);
+    let (time, record) = parse_race_part_2(input).unwrap();
     let criterion = (time * time - 4 * record) as f64;
     let criterion_sqrt = criterion.sqrt();
     let min = (time as f64 - criterion_sqrt) / 2.0;
@@ -86,7 +59,15 @@ mod tests {
     fn parse_test() {
         assert_eq!(
             parse_race(&vec!["Time:      7  15   30", "Distance:  9  40  200"].join("\n")),
-            vec![(7, 9), (15, 40), (30, 200)]
+            Ok(vec![(7, 9), (15, 40), (30, 200)])
+        );
+    }
+
+    #[test]
+    fn parse_part_2_test() {
+        assert_eq!(
+            parse_race_part_2(&vec!["Time:      7  15   30", "Distance:  9  40  200"].join("\n")),
+            Ok((71530, 940200))
         );
     }
 
@@ -97,4 +78,12 @@ mod tests {
             288
         );
     }
+
+    #[test]
+    fn solution_2_test() {
+        assert_eq!(
+            solution_2(&vec!["Time:      7  15   30", "Distance:  9  40  200"].join("\n")),
+            71503
+        );
+    }
 }
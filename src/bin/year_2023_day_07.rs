@@ -1,11 +1,16 @@
+use advent_of_code::parsing::{parse_lines, report, unsigned, Diagnostic};
 use aoc_helper::{AocDay, Puzzle};
 use nom::{
-    character::complete::{digit1, space0},
-    error::{Error, ErrorKind},
+    character::complete::space0,
+    error::{context, VerboseError, VerboseErrorKind},
     sequence::separated_pair,
-    IResult, Parser,
+    IResult,
 };
 
+const INPUT_PATH: &str = "inputs/2023/day7.txt";
+
+type ParseResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
+
 struct Game {
     hand_rank: u32,
     pot: u32,
@@ -21,19 +26,16 @@ enum HandRankKind {
     FiveOfAKind = 6,
 }
 
-fn parse_game(input: &str) -> IResult<&str, Game> {
-    let (input, (hand_rank, pot)) = separated_pair(
-        parse_hand_rank,
-        space0,
-        digit1.map(|x: &str| x.parse::<u32>().unwrap()),
-    )(input)?;
+fn parse_game(input: &str) -> ParseResult<'_, Game> {
+    let (input, (hand_rank, pot)) =
+        separated_pair(parse_hand_rank, space0, context("a bid", unsigned))(input)?;
 
     let game = Game { hand_rank, pot };
 
     Ok((input, game))
 }
 
-fn parse_hand_rank(input: &str) -> IResult<&str, u32> {
+fn parse_hand_rank(input: &str) -> ParseResult<'_, u32> {
     let mut cards = [0; 5];
     let mut chars = input.chars();
     for i in 0..5 {
@@ -44,7 +46,7 @@ fn parse_hand_rank(input: &str) -> IResult<&str, u32> {
             Some('J') => 11,
             Some('T') => 10,
             Some(x) if x.is_digit(10) => x.to_digit(10).unwrap() as u8,
-            _ => return Result::Err(nom::Err::Failure(Error::new(input, ErrorKind::Digit))),
+            _ => return Err(nom::Err::Failure(card_error(input, i))),
         }
     }
 
@@ -53,6 +55,20 @@ fn parse_hand_rank(input: &str) -> IResult<&str, u32> {
     Ok((&input[5..], hand_rank))
 }
 
+// points at the `index`th card of the hand, which is missing or not a card
+fn card_error(input: &str, index: usize) -> VerboseError<&str> {
+    let offset = input
+        .char_indices()
+        .nth(index)
+        .map_or(input.len(), |(offset, _)| offset);
+    VerboseError {
+        errors: vec![(
+            &input[offset..],
+            VerboseErrorKind::Context("a card (one of AKQJT98765432)"),
+        )],
+    }
+}
+
 fn compute_hand_rank(cards: &[u8]) -> u32 {
     let hand_rank_kind = compute_hand_rank_kind(cards) as u32;
     let lexicographic_rank = compute_lexicographic_rank(cards);
@@ -91,20 +107,16 @@ fn compute_hand_rank_kind(cards: &[u8]) -> HandRankKind {
     }
 }
 
-
-fn parse_game_2(input: &str) -> IResult<&str, Game> {
-    let (input, (hand_rank, pot)) = separated_pair(
-        parse_hand_rank_2,
-        space0,
-        digit1.map(|x: &str| x.parse::<u32>().unwrap()),
-    )(input)?;
+fn parse_game_2(input: &str) -> ParseResult<'_, Game> {
+    let (input, (hand_rank, pot)) =
+        separated_pair(parse_hand_rank_2, space0, context("a bid", unsigned))(input)?;
 
     let game = Game { hand_rank, pot };
 
     Ok((input, game))
 }
 
-fn parse_hand_rank_2(input: &str) -> IResult<&str, u32> {
+fn parse_hand_rank_2(input: &str) -> ParseResult<'_, u32> {
     let mut cards = [0; 5];
     let mut chars = input.chars();
     for i in 0..5 {
@@ -115,7 +127,7 @@ fn parse_hand_rank_2(input: &str) -> IResult<&str, u32> {
             Some('J') => 0,
             Some('T') => 10,
             Some(x) if x.is_digit(10) => x.to_digit(10).unwrap() as u8,
-            _ => return Result::Err(nom::Err::Failure(Error::new(input, ErrorKind::Digit))),
+            _ => return Err(nom::Err::Failure(card_error(input, i))),
         }
     }
 
@@ -174,14 +186,10 @@ fn compute_lexicographic_rank(cards: &[u8]) -> u32 {
     result
 }
 
-fn solution_1(input: &str) -> u64 {
+// `source` names where the input came from in diagnostics
+fn solution_1(input: &str, source: &str) -> Result<u64, Diagnostic> {
     let mut solution: u64 = 0;
-    let mut games = Vec::new();
-    for line in input.lines() {
-        if let Ok((_, game)) = parse_game(line) {
-            games.push(game);
-        }
-    }
+    let mut games = parse_lines(source, input, parse_game)?;
 
     games.sort_by_key(|x| x.hand_rank);
 
@@ -189,17 +197,12 @@ fn solution_1(input: &str) -> u64 {
         solution += game.pot as u64 * (index as u64 + 1);
     }
 
-    solution
+    Ok(solution)
 }
 
-fn solution_2(input: &str) -> u32 {
+fn solution_2(input: &str, source: &str) -> Result<u32, Diagnostic> {
     let mut solution: u32 = 0;
-    let mut games = Vec::new();
-    for line in input.lines() {
-        if let Ok((_, game)) = parse_game_2(line) {
-            games.push(game);
-        }
-    }
+    let mut games = parse_lines(source, input, parse_game_2)?;
 
     games.sort_by_key(|x| x.hand_rank);
 
@@ -207,13 +210,14 @@ fn solution_2(input: &str) -> u32 {
         solution += game.pot * (index as u32 + 1);
     }
 
-    solution 
+    Ok(solution)
 }
 
 fn main() {
     let mut aoc_day = AocDay::new(2023, 7);
-    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| solution_1(&x));
-    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| solution_2(&x));
+    aoc_day.input(INPUT_PATH);
+    let aoc_puzzle_part_1 = Puzzle::new(1, |x: String| report(solution_1(&x, INPUT_PATH)));
+    let aoc_puzzle_part_2 = Puzzle::new(2, |x: String| report(solution_2(&x, INPUT_PATH)));
     let _result_part_1 = aoc_day.run(&aoc_puzzle_part_1);
     let _result_part_2 = aoc_day.run(&aoc_puzzle_part_2);
 }
@@ -232,10 +236,7 @@ mod tests {
             "QQQJA 483",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            6440
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(6440));
     }
 
     #[test]
@@ -245,10 +246,7 @@ mod tests {
             "2AAAA 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            12
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(12));
     }
 
     #[test]
@@ -258,10 +256,7 @@ mod tests {
             "1AAAA 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            21
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(21));
     }
 
     #[test]
@@ -271,10 +266,7 @@ mod tests {
             "A2AAA 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            12
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(12));
     }
 
     #[test]
@@ -284,10 +276,7 @@ mod tests {
             "A1AAA 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            21
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(21));
     }
 
     #[test]
@@ -297,10 +286,7 @@ mod tests {
             "2KKKK 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            12
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(12));
     }
 
     #[test]
@@ -310,10 +296,7 @@ mod tests {
             "1AAAA 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            21
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(21));
     }
 
     #[test]
@@ -323,10 +306,7 @@ mod tests {
             "KKAAA 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            12
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(12));
     }
 
     #[test]
@@ -336,10 +316,7 @@ mod tests {
             "54321 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            12
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(12));
     }
 
     #[test]
@@ -349,10 +326,7 @@ mod tests {
             "2211A 1",
         ].join("\n");
 
-        assert_eq!(
-            solution_1(&input),
-            12
-        );
+        assert_eq!(solution_1(&input, "example"), Ok(12));
     }
 
     #[test]
@@ -365,9 +339,32 @@ mod tests {
             "QQQJA 483",
         ].join("\n");
 
+        assert_eq!(solution_2(&input, "example"), Ok(5905));
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let input = ["32T3K 765", "T55X5 684"].join("\n");
+
+        let diagnostic = solution_1(&input, "hands.txt").unwrap_err();
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 4));
+        assert_eq!(diagnostic.expected, "a card (one of AKQJT98765432)");
+        assert_eq!(
+            diagnostic.to_string(),
+            [
+                "error: expected a card (one of AKQJT98765432)",
+                " --> hands.txt:2:4",
+                "  |",
+                "2 | T55X5 684",
+                "  |    ^",
+            ]
+            .join("\n")
+        );
+
+        let diagnostic = solution_2("32T3K bid", "example").unwrap_err();
         assert_eq!(
-            solution_2(&input),
-            5905
+            (diagnostic.column, diagnostic.expected.as_str()),
+            (7, "a bid")
         );
     }
 }
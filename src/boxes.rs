@@ -0,0 +1,243 @@
+/// An axis aligned box in `N` dimensions covering `min[i]..max[i]` on every axis, the
+/// multi-dimensional counterpart of [`Interval`](crate::interval::Interval).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Box<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+impl<const N: usize> Box<N> {
+    pub fn new(min: [i64; N], max: [i64; N]) -> Self {
+        Box { min, max }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|axis| self.min[axis] >= self.max[axis])
+    }
+
+    /// Number of integer points inside the box.
+    pub fn volume(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..N)
+            .map(|axis| (self.max[axis] - self.min[axis]) as u64)
+            .product()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        (0..N).all(|axis| self.min[axis] <= point[axis] && point[axis] < self.max[axis])
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let mut intersection = *self;
+        for axis in 0..N {
+            intersection.min[axis] = self.min[axis].max(other.min[axis]);
+            intersection.max[axis] = self.max[axis].min(other.max[axis]);
+        }
+
+        if intersection.is_empty() {
+            None
+        } else {
+            Some(intersection)
+        }
+    }
+
+    /// Splits the part of `self` outside `other` into disjoint boxes.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(cut) = self.intersect(other) else {
+            return if self.is_empty() {
+                Vec::new()
+            } else {
+                vec![*self]
+            };
+        };
+
+        // slice off the slabs below and above the cut axis by axis, shrinking what remains
+        let mut pieces = Vec::new();
+        let mut remaining = *self;
+        for axis in 0..N {
+            if remaining.min[axis] < cut.min[axis] {
+                let mut below = remaining;
+                below.max[axis] = cut.min[axis];
+                pieces.push(below);
+                remaining.min[axis] = cut.min[axis];
+            }
+            if cut.max[axis] < remaining.max[axis] {
+                let mut above = remaining;
+                above.min[axis] = cut.max[axis];
+                pieces.push(above);
+                remaining.max[axis] = cut.max[axis];
+            }
+        }
+
+        pieces
+    }
+}
+
+/// A union of boxes stored as disjoint boxes, so the volume is a plain sum.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoxSet<const N: usize> {
+    boxes: Vec<Box<N>>,
+}
+
+impl<const N: usize> BoxSet<N> {
+    pub fn new() -> Self {
+        BoxSet { boxes: Vec::new() }
+    }
+
+    pub fn boxes(&self) -> &[Box<N>] {
+        &self.boxes
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.boxes.iter().map(|b| b.volume()).sum()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.boxes.iter().any(|b| b.contains(point))
+    }
+
+    pub fn insert(&mut self, added: Box<N>) {
+        let mut pieces = vec![added];
+        for existing in &self.boxes {
+            pieces = pieces
+                .iter()
+                .flat_map(|piece| piece.subtract(existing))
+                .collect();
+        }
+        self.boxes.extend(pieces);
+    }
+
+    pub fn remove(&mut self, removed: &Box<N>) {
+        self.boxes = self
+            .boxes
+            .iter()
+            .flat_map(|existing| existing.subtract(removed))
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for b in &other.boxes {
+            union.insert(*b);
+        }
+        union
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    fn cells<const N: usize>(b: &Box<N>) -> HashSet<[i64; N]> {
+        let mut cells = HashSet::new();
+        let mut point = b.min;
+        if b.is_empty() {
+            return cells;
+        }
+        loop {
+            cells.insert(point);
+            let mut axis = 0;
+            loop {
+                if axis == N {
+                    return cells;
+                }
+                point[axis] += 1;
+                if point[axis] < b.max[axis] {
+                    break;
+                }
+                point[axis] = b.min[axis];
+                axis += 1;
+            }
+        }
+    }
+
+    fn small_box<const N: usize>() -> impl Strategy<Value = Box<N>> {
+        (
+            prop::array::uniform(-4i64..4),
+            prop::array::uniform(0i64..5),
+        )
+            .prop_map(|(min, size): ([i64; N], [i64; N])| {
+                let mut max = min;
+                for axis in 0..N {
+                    max[axis] += size[axis];
+                }
+                Box::new(min, max)
+            })
+    }
+
+    #[test]
+    fn volume_test() {
+        assert_eq!(Box::new([0, 0, 0], [2, 3, 4]).volume(), 24);
+        assert_eq!(Box::new([-1, 5], [1, 5]).volume(), 0);
+    }
+
+    #[test]
+    fn subtract_test() {
+        let outer = Box::new([0, 0], [3, 3]);
+        let pieces = outer.subtract(&Box::new([1, 1], [2, 2]));
+
+        assert_eq!(pieces.len(), 4);
+        assert_eq!(pieces.iter().map(|b| b.volume()).sum::<u64>(), 8);
+        assert_eq!(outer.subtract(&Box::new([5, 5], [6, 6])), vec![outer]);
+    }
+
+    proptest! {
+        #[test]
+        fn subtract_matches_cells(a in small_box::<3>(), b in small_box::<3>()) {
+            let pieces = a.subtract(&b);
+            let expected = cells(&a).difference(&cells(&b)).copied().collect::<HashSet<_>>();
+
+            let mut covered = HashSet::new();
+            for piece in &pieces {
+                for cell in cells(piece) {
+                    prop_assert!(covered.insert(cell), "pieces overlap");
+                }
+            }
+            prop_assert_eq!(covered, expected);
+        }
+
+        #[test]
+        fn box_set_matches_cells(
+            operations in prop::collection::vec((any::<bool>(), small_box::<2>()), 0..12),
+        ) {
+            let mut set = BoxSet::new();
+            let mut expected = HashSet::new();
+
+            for (add, b) in operations {
+                if add {
+                    set.insert(b);
+                    expected.extend(cells(&b));
+                } else {
+                    set.remove(&b);
+                    for cell in cells(&b) {
+                        expected.remove(&cell);
+                    }
+                }
+            }
+
+            prop_assert_eq!(set.volume(), expected.len() as u64);
+            for x in -5..10 {
+                for y in -5..10 {
+                    prop_assert_eq!(set.contains([x, y]), expected.contains(&[x, y]));
+                }
+            }
+        }
+
+        #[test]
+        fn union_matches_cells(a in small_box::<3>(), b in small_box::<3>(), c in small_box::<3>()) {
+            let mut left = BoxSet::new();
+            left.insert(a);
+            left.insert(b);
+            let mut right = BoxSet::new();
+            right.insert(b);
+            right.insert(c);
+
+            let expected = cells(&a).into_iter().chain(cells(&b)).chain(cells(&c)).collect::<HashSet<_>>();
+            prop_assert_eq!(left.union(&right).volume(), expected.len() as u64);
+        }
+    }
+}
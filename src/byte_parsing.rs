@@ -0,0 +1,169 @@
+//! Allocation free parsers over `&[u8]` for hot loops. They return the parsed value and the rest
+//! of the input, or `None` when the input does not match; use [`crate::parsing`] where errors
+//! need to be explained.
+
+/// An integer that can be built one decimal digit at a time, with overflow checks.
+pub trait Integer: Copy {
+    const ZERO: Self;
+
+    /// `self * 10 + digit`, or `self * 10 - digit` when `negative`.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+
+            #[inline]
+            fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                let shifted = self.checked_mul(10)?;
+                if negative {
+                    shifted.checked_sub(digit as Self)
+                } else {
+                    shifted.checked_add(digit as Self)
+                }
+            }
+        })*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[inline]
+fn digits<T: Integer>(input: &[u8], negative: bool) -> Option<(T, &[u8])> {
+    let length = input.iter().take_while(|b| b.is_ascii_digit()).count();
+    if length == 0 {
+        return None;
+    }
+
+    let (digits, rest) = input.split_at(length);
+    let mut value = T::ZERO;
+    for digit in digits {
+        value = value.push_digit(digit - b'0', negative)?;
+    }
+    Some((value, rest))
+}
+
+/// Leading digits, `None` if there are none or the number overflows `T`.
+#[inline]
+pub fn unsigned<T: Integer>(input: &[u8]) -> Option<(T, &[u8])> {
+    digits(input, false)
+}
+
+/// Leading digits with an optional `+` or `-` sign.
+#[inline]
+pub fn signed<T: Integer>(input: &[u8]) -> Option<(T, &[u8])> {
+    match input.first() {
+        Some(b'-') => digits(&input[1..], true),
+        Some(b'+') => digits(&input[1..], false),
+        _ => digits(input, false),
+    }
+}
+
+/// Drops leading spaces, tabs and line breaks.
+#[inline]
+pub fn skip_whitespace(input: &[u8]) -> &[u8] {
+    let length = input.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    &input[length..]
+}
+
+/// Strips `tag` from the start of the input.
+#[inline]
+pub fn tag<'a>(input: &'a [u8], tag: &[u8]) -> Option<&'a [u8]> {
+    input.strip_prefix(tag)
+}
+
+/// The parts before and after the first `byte`.
+#[inline]
+pub fn split_once(input: &[u8], byte: u8) -> Option<(&[u8], &[u8])> {
+    let index = input.iter().position(|b| *b == byte)?;
+    Some((&input[..index], &input[index + 1..]))
+}
+
+/// The parts between occurrences of `byte`.
+pub fn split(input: &[u8], byte: u8) -> impl Iterator<Item = &[u8]> {
+    input.split(move |b| *b == byte)
+}
+
+/// The lines of the input without their `\n` or `\r\n`, like [`str::lines`].
+pub fn lines(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let empty = input.is_empty();
+    let input = input.strip_suffix(b"\n").unwrap_or(input);
+    split(input, b'\n')
+        .filter(move |_| !empty)
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+/// Whitespace separated unsigned numbers, stopping at the first token that is not one.
+pub fn unsigned_list<T: Integer>(input: &[u8]) -> Numbers<'_, T> {
+    Numbers {
+        input,
+        marker: std::marker::PhantomData,
+    }
+}
+
+/// Iterator returned by [`unsigned_list`], [`Numbers::rest`] is the unparsed input.
+pub struct Numbers<'a, T> {
+    input: &'a [u8],
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, T> Numbers<'a, T> {
+    pub fn rest(&self) -> &'a [u8] {
+        self.input
+    }
+}
+
+impl<T: Integer> Iterator for Numbers<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let (number, rest) = unsigned(skip_whitespace(self.input))?;
+        self.input = rest;
+        Some(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_test() {
+        assert_eq!(unsigned::<u32>(b"123 x"), Some((123, &b" x"[..])));
+        assert_eq!(unsigned::<u8>(b"256"), None);
+        assert_eq!(unsigned::<u32>(b"x1"), None);
+        assert_eq!(signed::<i8>(b"-128,"), Some((-128, &b","[..])));
+        assert_eq!(signed::<i8>(b"+127"), Some((127, &b""[..])));
+        assert_eq!(signed::<u32>(b"-1"), None);
+        assert_eq!(signed::<i32>(b"-"), None);
+    }
+
+    #[test]
+    fn split_test() {
+        assert_eq!(
+            split_once(b"Game 1: 3 blue", b':'),
+            Some((&b"Game 1"[..], &b" 3 blue"[..]))
+        );
+        assert_eq!(
+            split(b"a;b;", b';').collect::<Vec<_>>(),
+            [&b"a"[..], b"b", b""]
+        );
+        assert_eq!(
+            lines(b"ab\r\ncd\n").collect::<Vec<_>>(),
+            [&b"ab"[..], &b"cd"[..]]
+        );
+        assert_eq!(lines(b"").count(), 0);
+        assert_eq!(lines(b"\n").count(), 1);
+    }
+
+    #[test]
+    fn unsigned_list_test() {
+        let mut numbers = unsigned_list::<u32>(b" 41 48  6 | 83");
+
+        assert_eq!(numbers.by_ref().collect::<Vec<_>>(), [41, 48, 6]);
+        assert_eq!(numbers.rest(), b" | 83");
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Step at which the repeating part begins.
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Steps a state forward while remembering every state seen, so that a repeating state can be
+/// used to jump straight to any step.
+pub struct Simulation<S, F> {
+    step: F,
+    history: Vec<S>,
+    seen: HashMap<u64, Vec<usize>>,
+    cycle: Option<Cycle>,
+}
+
+fn hash_of<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<S: Hash + Eq + Clone, F: FnMut(&S) -> S> Simulation<S, F> {
+    pub fn new(initial: S, step: F) -> Self {
+        let mut seen = HashMap::new();
+        seen.insert(hash_of(&initial), vec![0]);
+
+        Self {
+            step,
+            history: vec![initial],
+            seen,
+            cycle: None,
+        }
+    }
+
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Number of states simulated so far, including the initial one.
+    pub fn steps_simulated(&self) -> usize {
+        self.history.len()
+    }
+
+    fn advance(&mut self) {
+        let next = (self.step)(self.history.last().unwrap());
+        let hash = hash_of(&next);
+        let index = self.history.len();
+
+        let indices = self.seen.entry(hash).or_default();
+        if let Some(&start) = indices.iter().find(|&&i| self.history[i] == next) {
+            self.cycle = Some(Cycle {
+                start,
+                length: index - start,
+            });
+        } else {
+            indices.push(index);
+            self.history.push(next);
+        }
+    }
+
+    /// Simulates until a state repeats, giving up after `max_steps` new states.
+    pub fn find_cycle(&mut self, max_steps: usize) -> Option<Cycle> {
+        let limit = self.history.len() + max_steps;
+        while self.cycle.is_none() && self.history.len() < limit {
+            self.advance();
+        }
+        self.cycle
+    }
+
+    /// The state after `n` steps, fast-forwarding through the cycle once it has been found.
+    pub fn state_at(&mut self, n: usize) -> &S {
+        while self.cycle.is_none() && self.history.len() <= n {
+            self.advance();
+        }
+
+        match self.cycle {
+            Some(cycle) if n >= self.history.len() => {
+                &self.history[cycle.start + (n - cycle.start) % cycle.length]
+            }
+            _ => &self.history[n],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::BitTable;
+
+    #[test]
+    fn cycle_with_lead_in_test() {
+        // 0, 1, 2, 3, 4, 2, 3, 4, ...
+        let mut simulation = Simulation::new(0u32, |x| if *x < 4 { x + 1 } else { 2 });
+
+        assert_eq!(
+            simulation.find_cycle(100),
+            Some(Cycle {
+                start: 2,
+                length: 3
+            })
+        );
+        assert_eq!(*simulation.state_at(3), 3);
+        assert_eq!(*simulation.state_at(5), 2);
+        assert_eq!(
+            *simulation.state_at(1_000_000_000),
+            2 + (1_000_000_000 - 2) % 3
+        );
+    }
+
+    #[test]
+    fn find_cycle_limit_test() {
+        let mut simulation = Simulation::new(0u64, |x| x + 1);
+
+        assert_eq!(simulation.find_cycle(10), None);
+        assert_eq!(simulation.steps_simulated(), 11);
+        assert_eq!(*simulation.state_at(20), 20);
+    }
+
+    #[test]
+    fn grid_state_test() {
+        let mut initial = BitTable::new(4, 1);
+        initial.set(0, 0, true);
+
+        let mut simulation = Simulation::new(initial, |grid: &BitTable| {
+            let mut next = BitTable::new(4, 1);
+            for x in 0..4 {
+                next.set((x + 1) % 4, 0, grid.is_set(x, 0));
+            }
+            next
+        });
+
+        assert!(simulation.state_at(1_000_000_001).is_set(1, 0));
+        assert_eq!(
+            simulation.cycle(),
+            Some(Cycle {
+                start: 0,
+                length: 4
+            })
+        );
+    }
+}
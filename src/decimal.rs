@@ -0,0 +1,296 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// An exact decimal number, `mantissa * 10^-scale`.
+///
+/// Parses `1234.5`, `1234,5`, `1,234.5`, `1.234,5`, `1 234,5` and `1'234.5`: when both `.` and
+/// `,` appear the last one is the decimal separator, a lone `.` or `,` is always decimal.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    Empty,
+    InvalidCharacter(char),
+    /// A thousands separator not followed by exactly three digits.
+    MisplacedSeparator,
+    /// More significant digits than fit into an `i128`.
+    TooManyDigits,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDecimalError::Empty => write!(f, "empty decimal"),
+            ParseDecimalError::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            ParseDecimalError::MisplacedSeparator => write!(f, "misplaced thousands separator"),
+            ParseDecimalError::TooManyDigits => write!(f, "too many digits"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal {
+        mantissa: 0,
+        scale: 0,
+    };
+
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Decimal { mantissa, scale }
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Number of digits after the decimal point.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// The same value with `scale` digits after the decimal point, `None` if it does not fit.
+    pub fn rescale(&self, scale: u32) -> Option<Decimal> {
+        if scale < self.scale {
+            let factor = 10i128.checked_pow(self.scale - scale)?;
+            return (self.mantissa % factor == 0).then_some(Decimal {
+                mantissa: self.mantissa / factor,
+                scale,
+            });
+        }
+        let factor = 10i128.checked_pow(scale - self.scale)?;
+        Some(Decimal {
+            mantissa: self.mantissa.checked_mul(factor)?,
+            scale,
+        })
+    }
+
+    /// Drops trailing zeros after the decimal point.
+    pub fn normalize(&self) -> Decimal {
+        let mut result = *self;
+        while result.scale > 0 && result.mantissa % 10 == 0 {
+            result.mantissa /= 10;
+            result.scale -= 1;
+        }
+        result
+    }
+
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        let (left, right) = (self.rescale(scale)?, other.rescale(scale)?);
+        Some(Decimal {
+            mantissa: left.mantissa.checked_add(right.mantissa)?,
+            scale,
+        })
+    }
+
+    /// Rounded to the nearest `f64`, for display and plotting only.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, input.strip_prefix('+').unwrap_or(input)),
+        };
+        if digits.is_empty() {
+            return Err(ParseDecimalError::Empty);
+        }
+
+        // the decimal separator is the last `.` or `,`, unless it is the one kind that repeats
+        let decimal_index = digits.rfind(['.', ',']).filter(|&index| {
+            let separator = digits.as_bytes()[index] as char;
+            digits.matches(separator).count() == 1
+        });
+        let (integer, fraction) = match decimal_index {
+            Some(index) => (&digits[..index], &digits[index + 1..]),
+            None => (digits, ""),
+        };
+
+        let mut mantissa: i128 = 0;
+        let mut push = |c: char| -> Result<(), ParseDecimalError> {
+            let digit = c
+                .to_digit(10)
+                .ok_or(ParseDecimalError::InvalidCharacter(c))? as i128;
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(digit))
+                .ok_or(ParseDecimalError::TooManyDigits)?;
+            Ok(())
+        };
+
+        let mut groups = integer.split(['.', ',', ' ', '\'']);
+        let first = groups.next().unwrap_or_default();
+        if first.is_empty() && integer.len() > first.len() {
+            return Err(ParseDecimalError::MisplacedSeparator);
+        }
+        first.chars().try_for_each(&mut push)?;
+        for group in groups {
+            if group.len() != 3 {
+                return Err(ParseDecimalError::MisplacedSeparator);
+            }
+            group.chars().try_for_each(&mut push)?;
+        }
+        fraction.chars().try_for_each(&mut push)?;
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(ParseDecimalError::Empty);
+        }
+
+        Ok(Decimal {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: fraction.len() as u32,
+        })
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        self.checked_add(&other).expect("decimal overflow")
+    }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::ZERO, Add::add)
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        if let (Some(left), Some(right)) = (self.rescale(scale), other.rescale(scale)) {
+            return left.mantissa.cmp(&right.mantissa);
+        }
+
+        // too far apart to align in an i128, compare the digits instead
+        let sign = self.mantissa.signum().cmp(&other.mantissa.signum());
+        let magnitude = |decimal: &Decimal| {
+            let digits = decimal.to_string();
+            let digits = digits.trim_start_matches('-').to_string();
+            let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+            (
+                integer.trim_start_matches('0').to_string(),
+                fraction.trim_end_matches('0').to_string(),
+            )
+        };
+        let (left, right) = (magnitude(self), magnitude(other));
+        let ordering = (left.0.len(), &left.0, &left.1).cmp(&(right.0.len(), &right.0, &right.1));
+
+        sign.then(if self.mantissa < 0 {
+            ordering.reverse()
+        } else {
+            ordering
+        })
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(input: &str) -> Decimal {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(decimal("1234.5"), Decimal::new(12345, 1));
+        assert_eq!(decimal("1234,5"), Decimal::new(12345, 1));
+        assert_eq!(decimal("1,234.5"), Decimal::new(12345, 1));
+        assert_eq!(decimal("1.234,5"), Decimal::new(12345, 1));
+        assert_eq!(decimal("1.234.567"), Decimal::new(1234567, 0));
+        assert_eq!(decimal("1 234,5"), Decimal::new(12345, 1));
+        assert_eq!(decimal("-0,25"), Decimal::new(-25, 2));
+        assert_eq!(decimal(",5"), Decimal::new(5, 1));
+        assert_eq!(
+            decimal("0.1234567890123456789012345").to_string(),
+            "0.1234567890123456789012345"
+        );
+
+        assert_eq!("".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+        assert_eq!("-".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+        assert_eq!(
+            "1,2,3".parse::<Decimal>(),
+            Err(ParseDecimalError::MisplacedSeparator)
+        );
+        assert_eq!(
+            "1.5x".parse::<Decimal>(),
+            Err(ParseDecimalError::InvalidCharacter('x'))
+        );
+        assert_eq!(
+            "1".repeat(40).parse::<Decimal>(),
+            Err(ParseDecimalError::TooManyDigits)
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(Decimal::new(5, 3).to_string(), "0.005");
+        assert_eq!(Decimal::new(-12345, 2).to_string(), "-123.45");
+        assert_eq!(Decimal::new(7, 0).to_string(), "7");
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        // 0.1 + 0.2 is exactly 0.3, unlike with floats
+        assert_eq!(decimal("0.1") + decimal("0,2"), decimal("0.3"));
+        assert_eq!(decimal("0.30"), decimal("0.3"));
+        assert!(decimal("1.000000000000000000001") > decimal("1"));
+        assert!(decimal("-2") < decimal("-1.5"));
+        assert!(Decimal::new(i128::MAX, 0) > Decimal::new(1, 38));
+        assert!(Decimal::new(-i128::MAX, 0) < Decimal::new(-1, 38));
+        assert_eq!(Decimal::new(5, 39), Decimal::new(5, 39));
+
+        let total: Decimal = ["1,5", "2.25", "-0.75"].into_iter().map(decimal).sum();
+        assert_eq!(total.to_string(), "3.00");
+        assert_eq!(
+            Decimal::new(i128::MAX, 0).checked_add(&Decimal::new(1, 0)),
+            None
+        );
+    }
+}
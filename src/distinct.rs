@@ -0,0 +1,200 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::io::{self, Read};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The last `size` symbols of a stream together with a count per symbol, so that pushing a
+/// symbol and asking whether the window is all distinct are both O(1).
+pub struct DistinctWindow<T> {
+    size: usize,
+    items: VecDeque<T>,
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq + Clone> DistinctWindow<T> {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            items: VecDeque::with_capacity(size + 1),
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        *self.counts.entry(item.clone()).or_insert(0) += 1;
+        self.items.push_back(item);
+
+        if self.items.len() > self.size {
+            let oldest = self.items.pop_front().unwrap();
+            let count = self.counts.get_mut(&oldest).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() == self.size
+    }
+
+    /// Number of different symbols currently in the window.
+    pub fn distinct_count(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn all_distinct(&self) -> bool {
+        self.is_full() && self.counts.len() == self.size
+    }
+}
+
+/// Number of items consumed when the last `size` items are first all different.
+pub fn first_distinct_window<T: Hash + Eq + Clone>(
+    items: impl IntoIterator<Item = T>,
+    size: usize,
+) -> Option<usize> {
+    let mut window = DistinctWindow::new(size);
+
+    for (index, item) in items.into_iter().enumerate() {
+        window.push(item);
+        if window.all_distinct() {
+            return Some(index + 1);
+        }
+    }
+
+    None
+}
+
+/// Scans a byte stream chunk by chunk, yielding the byte offset just past every window of `size`
+/// all different bytes. The window state carries over between chunks, so memory use does not
+/// depend on the length of the stream.
+pub struct StreamMarkers<R> {
+    reader: R,
+    chunk: Vec<u8>,
+    position: usize,
+    filled: usize,
+    offset: u64,
+    window: DistinctWindow<u8>,
+}
+
+impl<R: Read> StreamMarkers<R> {
+    pub fn new(reader: R, size: usize) -> Self {
+        Self {
+            reader,
+            chunk: vec![0; CHUNK_SIZE],
+            position: 0,
+            filled: 0,
+            offset: 0,
+            window: DistinctWindow::new(size),
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamMarkers<R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.position == self.filled {
+                match self.reader.read(&mut self.chunk) {
+                    Ok(0) => return None,
+                    Ok(filled) => {
+                        self.position = 0;
+                        self.filled = filled;
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+
+            while self.position < self.filled {
+                self.window.push(self.chunk[self.position]);
+                self.position += 1;
+                self.offset += 1;
+
+                if self.window.all_distinct() {
+                    return Some(Ok(self.offset));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_count_test() {
+        let mut window = DistinctWindow::new(3);
+        for item in "aab".chars() {
+            window.push(item);
+        }
+
+        assert!(window.is_full());
+        assert_eq!(window.distinct_count(), 2);
+        assert!(!window.all_distinct());
+
+        window.push('c');
+        assert!(window.all_distinct());
+    }
+
+    #[test]
+    fn first_distinct_window_test() {
+        assert_eq!(
+            first_distinct_window("mjqjpqmgbljsphdztnvjfqwrcgsmlb".chars(), 4),
+            Some(7)
+        );
+        assert_eq!(
+            first_distinct_window("mjqjpqmgbljsphdztnvjfqwrcgsmlb".chars(), 14),
+            Some(19)
+        );
+        assert_eq!(first_distinct_window("aaaa".chars(), 2), None);
+    }
+
+    #[test]
+    fn arbitrary_bytes_test() {
+        let bytes = [0u8, 0, 255, b'\n', b'A', 0];
+
+        assert_eq!(first_distinct_window(bytes, 4), Some(5));
+        assert_eq!(first_distinct_window("ääöü".chars(), 3), Some(4));
+    }
+
+    // hands out at most `chunk` bytes per read, to force windows across chunk boundaries
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let length = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..length].copy_from_slice(&self.data[..length]);
+            self.data = &self.data[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn stream_markers_test() {
+        let data = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+        for chunk in 1..5 {
+            let markers = StreamMarkers::new(Trickle { data, chunk }, 14)
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap();
+
+            assert_eq!(markers, vec![19, 25, 26, 27, 28, 29, 30]);
+        }
+    }
+
+    #[test]
+    fn stream_markers_every_position_test() {
+        let markers = StreamMarkers::new(&b"abcabd"[..], 3)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(markers, vec![3, 4, 5, 6]);
+    }
+}
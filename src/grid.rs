@@ -0,0 +1,472 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A dense, fixed size grid addressed by `(x, y)` with the origin in the top left corner.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: u32,
+    height: u32,
+}
+
+pub type BitTable = Grid<bool>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    fn offsets(&self) -> &'static [(i64, i64)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// Read access shared by the dense and sparse grids.
+pub trait GridView {
+    type Cell;
+
+    /// Inclusive `(min, max)` corners of the occupied area, `None` when there is nothing to show.
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))>;
+
+    fn cell(&self, x: i64, y: i64) -> Option<&Self::Cell>;
+
+    /// Draws the bounded area as lines of text, using `empty` where there is no cell.
+    fn render(&self, empty: char, glyph: impl Fn(&Self::Cell) -> char) -> String {
+        let mut output = String::new();
+
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() {
+            for y in min_y..=max_y {
+                if y > min_y {
+                    output.push('\n');
+                }
+                for x in min_x..=max_x {
+                    output.push(self.cell(x, y).map_or(empty, &glyph));
+                }
+            }
+        }
+
+        output
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridError {
+    /// Line `row`, counted from 0, does not have as many cells as the first line.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} cells, expected {}",
+                row + 1,
+                found,
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Component {
+    pub size: usize,
+    /// Inclusive bounding box as `(min, max)` corners.
+    pub min: (u32, u32),
+    pub max: (u32, u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Components {
+    /// Index into `components` for every labelled cell, `None` for cells that were not passable.
+    pub labels: Grid<Option<usize>>,
+    pub components: Vec<Component>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: u32, height: u32, value: T) -> Self {
+        Self {
+            cells: vec![value; width as usize * height as usize],
+            width,
+            height,
+        }
+    }
+
+    pub fn set_around(&mut self, x: u32, y: u32, value: T) {
+        self.set(x, y, value.clone());
+        for (nx, ny) in self
+            .neighbours(x, y, Connectivity::Eight)
+            .collect::<Vec<_>>()
+        {
+            self.set(nx, ny, value.clone());
+        }
+    }
+}
+
+impl<T: Clone + Default> Grid<T> {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::filled(width, height, T::default())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from line based text, every line needs as many cells as the first one.
+    pub fn parse(input: &str, cell: impl Fn(char) -> T) -> Result<Self, GridError> {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        let mut cells = Vec::new();
+        let mut height = 0;
+
+        for (row, line) in input.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&cell));
+            if cells.len() - before != width {
+                return Err(GridError::RaggedRow {
+                    row,
+                    expected: width,
+                    found: cells.len() - before,
+                });
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width as u32,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(x as usize + y as usize * self.width as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Option<&T> {
+        self.index(x, y).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
+        self.index(x, y).map(|index| &mut self.cells[index])
+    }
+
+    /// Writes are ignored outside of the grid.
+    pub fn set(&mut self, x: u32, y: u32, value: T) {
+        if let Some(index) = self.index(x, y) {
+            self.cells[index] = value;
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (u32, u32)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn neighbours(
+        &self,
+        x: u32,
+        y: u32,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (u32, u32)> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        connectivity
+            .offsets()
+            .iter()
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(move |(nx, ny)| (0..width).contains(nx) && (0..height).contains(ny))
+            .map(|(nx, ny)| (nx as u32, ny as u32))
+    }
+
+    fn is_border(&self, x: u32, y: u32) -> bool {
+        x == 0 || y == 0 || x + 1 == self.width || y + 1 == self.height
+    }
+
+    // `visit` sees every newly reached cell once, in the order they are reached
+    fn fill_from(
+        &self,
+        reached: &mut BitTable,
+        starts: impl IntoIterator<Item = (u32, u32)>,
+        connectivity: Connectivity,
+        passable: &impl Fn(&T) -> bool,
+        mut visit: impl FnMut(u32, u32),
+    ) -> Component {
+        let mut queue = VecDeque::new();
+        let mut component = Component {
+            size: 0,
+            min: (u32::MAX, u32::MAX),
+            max: (0, 0),
+        };
+
+        for (x, y) in starts {
+            if self.get(x, y).is_some_and(passable) && !reached.is_set(x, y) {
+                reached.set(x, y, true);
+                queue.push_back((x, y));
+            }
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            visit(x, y);
+            component.size += 1;
+            component.min = (component.min.0.min(x), component.min.1.min(y));
+            component.max = (component.max.0.max(x), component.max.1.max(y));
+
+            for (nx, ny) in self.neighbours(x, y, connectivity) {
+                if !reached.is_set(nx, ny) && self.get(nx, ny).is_some_and(passable) {
+                    reached.set(nx, ny, true);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        component
+    }
+
+    /// Marks every cell reachable from `start` through passable cells.
+    pub fn flood_fill(
+        &self,
+        start: (u32, u32),
+        connectivity: Connectivity,
+        passable: impl Fn(&T) -> bool,
+    ) -> BitTable {
+        let mut reached = BitTable::new(self.width, self.height);
+        self.fill_from(&mut reached, [start], connectivity, &passable, |_, _| {});
+        reached
+    }
+
+    /// Labels every connected region of passable cells, in row major order of their first cell.
+    pub fn label_components(
+        &self,
+        connectivity: Connectivity,
+        passable: impl Fn(&T) -> bool,
+    ) -> Components {
+        let mut reached = BitTable::new(self.width, self.height);
+        let mut labels = Grid::filled(self.width, self.height, None);
+        let mut components = Vec::new();
+
+        for (x, y) in self.positions() {
+            if reached.is_set(x, y) || !self.get(x, y).is_some_and(&passable) {
+                continue;
+            }
+
+            let label = Some(components.len());
+            let component =
+                self.fill_from(&mut reached, [(x, y)], connectivity, &passable, |rx, ry| {
+                    labels.set(rx, ry, label)
+                });
+            components.push(component);
+        }
+
+        Components { labels, components }
+    }
+
+    /// Marks the passable cells that can not be reached from a passable cell on the border.
+    pub fn enclosed(&self, connectivity: Connectivity, passable: impl Fn(&T) -> bool) -> BitTable {
+        let mut outside = BitTable::new(self.width, self.height);
+        let border = self.positions().filter(|&(x, y)| self.is_border(x, y));
+        self.fill_from(&mut outside, border, connectivity, &passable, |_, _| {});
+
+        let mut enclosed = BitTable::new(self.width, self.height);
+        for (x, y) in self.positions() {
+            if !outside.is_set(x, y) && self.get(x, y).is_some_and(&passable) {
+                enclosed.set(x, y, true);
+            }
+        }
+        enclosed
+    }
+}
+
+impl<T> GridView for Grid<T> {
+    type Cell = T;
+
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        if self.width == 0 || self.height == 0 {
+            None
+        } else {
+            Some(((0, 0), (self.width as i64 - 1, self.height as i64 - 1)))
+        }
+    }
+
+    fn cell(&self, x: i64, y: i64) -> Option<&T> {
+        if x < 0 || y < 0 || x > u32::MAX as i64 || y > u32::MAX as i64 {
+            return None;
+        }
+        self.get(x as u32, y as u32)
+    }
+}
+
+impl BitTable {
+    pub fn is_set(&self, x: u32, y: u32) -> bool {
+        self.get(x, y).copied().unwrap_or(false)
+    }
+
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|cell| **cell).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walls(rows: &[&str]) -> Grid<char> {
+        Grid::parse(&rows.join("\n"), |c| c).unwrap()
+    }
+
+    #[test]
+    fn set_around_test() {
+        let mut table = BitTable::new(3, 3);
+        table.set_around(0, 0, true);
+
+        assert_eq!(table.count(), 4);
+        assert!(table.is_set(1, 1));
+        assert!(!table.is_set(2, 0));
+    }
+
+    #[test]
+    fn flood_fill_connectivity_test() {
+        let grid = walls(&["..#", "##.", "..."]);
+
+        let four = grid.flood_fill((0, 0), Connectivity::Four, |c| *c == '.');
+        let eight = grid.flood_fill((0, 0), Connectivity::Eight, |c| *c == '.');
+
+        assert_eq!(four.count(), 2);
+        assert_eq!(eight.count(), 6);
+        assert!(grid
+            .flood_fill((2, 1), Connectivity::Eight, |c| *c == '.')
+            .is_set(0, 2));
+    }
+
+    #[test]
+    fn flood_fill_blocked_start_test() {
+        let grid = walls(&["#."]);
+
+        assert_eq!(
+            grid.flood_fill((0, 0), Connectivity::Four, |c| *c == '.')
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn label_components_test() {
+        let grid = walls(&["..#..", "..#..", "###.#", "#...#"]);
+        let components = grid.label_components(Connectivity::Four, |c| *c == '.');
+
+        assert_eq!(
+            components.components,
+            vec![
+                Component {
+                    size: 4,
+                    min: (0, 0),
+                    max: (1, 1)
+                },
+                Component {
+                    size: 8,
+                    min: (1, 0),
+                    max: (4, 3)
+                },
+            ]
+        );
+        assert_eq!(components.labels.get(1, 1), Some(&Some(0)));
+        assert_eq!(components.labels.get(1, 3), Some(&Some(1)));
+        assert_eq!(components.labels.get(2, 0), Some(&None));
+    }
+
+    #[test]
+    fn enclosed_test() {
+        let grid = walls(&[".....", ".##..", ".#.#.", "..##."]);
+
+        let four = grid.enclosed(Connectivity::Four, |c| *c == '.');
+        let eight = grid.enclosed(Connectivity::Eight, |c| *c == '.');
+
+        assert_eq!(four.count(), 1);
+        assert!(four.is_set(2, 2));
+        assert_eq!(eight.count(), 0);
+    }
+
+    #[test]
+    fn parse_ragged_test() {
+        assert_eq!(
+            Grid::parse("abc\nd", |c| c),
+            Err(GridError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 1,
+            })
+        );
+        assert_eq!(Grid::parse("", |c| c).map(|grid| grid.height()), Ok(0));
+    }
+
+    #[test]
+    fn label_components_checkerboard_test() {
+        let grid = Grid::filled(400, 400, ());
+        let components = grid.label_components(Connectivity::Four, |_| true);
+        assert_eq!(components.components.len(), 1);
+
+        let rows = (0..400)
+            .map(|y| {
+                (0..400)
+                    .map(|x| if (x + y) % 2 == 0 { '.' } else { '#' })
+                    .collect()
+            })
+            .collect::<Vec<String>>();
+        let grid = Grid::parse(&rows.join("\n"), |c| c).unwrap();
+        let components = grid.label_components(Connectivity::Four, |c| *c == '.');
+
+        assert_eq!(components.components.len(), 80_000);
+        assert_eq!(components.labels.get(399, 399), Some(&Some(79_999)));
+    }
+
+    #[test]
+    fn large_grid_index_test() {
+        // 70_000 * 70_000 cells do not fit into a u32
+        let grid = Grid::filled(70_000, 70_000, ());
+        assert_eq!(grid.get(69_999, 69_999), Some(&()));
+        assert_eq!(grid.get(70_000, 0), None);
+
+        assert_eq!(grid.index(69_999, 69_999), Some(4_899_999_999));
+    }
+
+    #[test]
+    fn render_test() {
+        let rows = ["..#", "#.."];
+        let grid = walls(&rows);
+
+        assert_eq!(grid.render(' ', |c| *c), rows.join("\n"));
+    }
+}
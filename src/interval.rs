@@ -0,0 +1,251 @@
+use std::fmt;
+
+/// A half open range of `width` values starting at `start`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub start: u64,
+    pub width: u64,
+}
+
+impl Interval {
+    pub fn new(start: u64, width: u64) -> Self {
+        Interval { start, width }
+    }
+
+    pub fn from_bounds(start: u64, end: u64) -> Self {
+        Interval::new(start, end.saturating_sub(start))
+    }
+
+    /// First value after the interval.
+    pub fn end(&self) -> u64 {
+        self.start + self.width
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.start <= value && value < self.end()
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.start + other.width && other.start < self.start + self.width
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            let start = self.start.max(other.start);
+            let end = (self.start + self.width).min(other.start + other.width);
+            Some(Interval::new(start, end - start))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end())
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non-adjacent intervals.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut intervals = iter
+            .into_iter()
+            .filter(|interval| !interval.is_empty())
+            .collect::<Vec<_>>();
+        intervals.sort_by_key(|interval| interval.start);
+
+        let mut normalized: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match normalized.last_mut() {
+                Some(last) if interval.start <= last.end() => {
+                    let end = last.end().max(interval.end());
+                    last.width = end - last.start;
+                }
+                _ => normalized.push(interval),
+            }
+        }
+
+        IntervalSet {
+            intervals: normalized,
+        }
+    }
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(|interval| interval.width).sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end() <= value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        *self = self.intervals.iter().copied().chain([interval]).collect();
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .chain(other.intervals.iter())
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(intersect) = a.intersect(&b) {
+                result.push(intersect);
+            }
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        // intersections of normalized sets are already sorted and disjoint
+        IntervalSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => {
+                let bounds = Interval::from_bounds(first.start, last.end());
+                self.intersection(&other.complement(bounds))
+            }
+            _ => IntervalSet::new(),
+        }
+    }
+
+    /// The values inside `bounds` that are not in the set.
+    pub fn complement(&self, bounds: Interval) -> IntervalSet {
+        let mut result = Vec::new();
+        let mut start = bounds.start;
+
+        for interval in &self.intervals {
+            if interval.end() <= start {
+                continue;
+            }
+            if interval.start >= bounds.end() {
+                break;
+            }
+            if interval.start > start {
+                result.push(Interval::from_bounds(start, interval.start));
+            }
+            start = interval.end();
+        }
+
+        if start < bounds.end() {
+            result.push(Interval::from_bounds(start, bounds.end()));
+        }
+
+        IntervalSet { intervals: result }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(bounds: &[(u64, u64)]) -> IntervalSet {
+        bounds
+            .iter()
+            .map(|&(start, end)| Interval::from_bounds(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(
+            set(&[(5, 8), (0, 2), (2, 3), (7, 10), (12, 12)]),
+            set(&[(0, 3), (5, 10)])
+        );
+        assert_eq!(set(&[(0, 3), (5, 10)]).intervals().len(), 2);
+    }
+
+    #[test]
+    fn union_test() {
+        assert_eq!(
+            set(&[(0, 3), (8, 10)]).union(&set(&[(3, 5), (9, 12)])),
+            set(&[(0, 5), (8, 12)])
+        );
+    }
+
+    #[test]
+    fn intersection_test() {
+        assert_eq!(
+            set(&[(0, 5), (8, 12)]).intersection(&set(&[(3, 9), (11, 20)])),
+            set(&[(3, 5), (8, 9), (11, 12)])
+        );
+    }
+
+    #[test]
+    fn difference_test() {
+        assert_eq!(
+            set(&[(0, 10), (20, 30)]).difference(&set(&[(2, 4), (8, 22), (29, 40)])),
+            set(&[(0, 2), (4, 8), (22, 29)])
+        );
+    }
+
+    #[test]
+    fn complement_test() {
+        let values = set(&[(2, 4), (6, 8)]);
+
+        assert_eq!(
+            values.complement(Interval::from_bounds(0, 10)),
+            set(&[(0, 2), (4, 6), (8, 10)])
+        );
+        assert_eq!(
+            values.complement(Interval::from_bounds(3, 7)),
+            set(&[(4, 6)])
+        );
+    }
+
+    #[test]
+    fn contains_test() {
+        let values = set(&[(2, 4), (6, 8)]);
+
+        assert!(values.contains(3));
+        assert!(!values.contains(4));
+        assert!(values.contains(6));
+        assert_eq!(values.len(), 4);
+        assert_eq!(values.min(), Some(2));
+    }
+}
@@ -0,0 +1,349 @@
+use std::cmp::Ordering;
+
+use crate::interval::Interval;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    interval: Interval,
+    value: T,
+    // largest interval end in this subtree
+    max: u64,
+    height: u32,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+/// An AVL tree of intervals with payloads, ordered by start and augmented with the largest end
+/// below every node so overlap queries can skip whole subtrees.
+pub struct IntervalTree<T> {
+    root: Link<T>,
+    len: usize,
+}
+
+fn key(interval: &Interval) -> (u64, u64) {
+    (interval.start, interval.width)
+}
+
+fn height<T>(link: &Link<T>) -> u32 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn max<T>(link: &Link<T>) -> u64 {
+    link.as_ref().map_or(0, |node| node.max)
+}
+
+impl<T> Node<T> {
+    fn new(interval: Interval, value: T) -> Box<Self> {
+        Box::new(Node {
+            interval,
+            value,
+            max: interval.end(),
+            height: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+        self.max = self
+            .interval
+            .end()
+            .max(max(&self.left))
+            .max(max(&self.right));
+    }
+
+    fn balance_factor(&self) -> i64 {
+        height(&self.left) as i64 - height(&self.right) as i64
+    }
+}
+
+fn rotate_right<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    node.update();
+    left.right = Some(node);
+    left.update();
+    left
+}
+
+fn rotate_left<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    node.update();
+    right.left = Some(node);
+    right.update();
+    right
+}
+
+fn rebalance<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    node.update();
+
+    if node.balance_factor() > 1 {
+        if node.left.as_ref().unwrap().balance_factor() < 0 {
+            node.left = Some(rotate_left(node.left.take().unwrap()));
+        }
+        rotate_right(node)
+    } else if node.balance_factor() < -1 {
+        if node.right.as_ref().unwrap().balance_factor() > 0 {
+            node.right = Some(rotate_right(node.right.take().unwrap()));
+        }
+        rotate_left(node)
+    } else {
+        node
+    }
+}
+
+fn insert<T>(link: Link<T>, interval: Interval, value: T) -> Box<Node<T>> {
+    match link {
+        None => Node::new(interval, value),
+        Some(mut node) => {
+            if key(&interval) < key(&node.interval) {
+                node.left = Some(insert(node.left.take(), interval, value));
+            } else {
+                node.right = Some(insert(node.right.take(), interval, value));
+            }
+            rebalance(node)
+        }
+    }
+}
+
+fn remove_min<T>(mut node: Box<Node<T>>) -> (Link<T>, Box<Node<T>>) {
+    match node.left.take() {
+        None => (node.right.take(), node),
+        Some(left) => {
+            let (left, min) = remove_min(left);
+            node.left = left;
+            (Some(rebalance(node)), min)
+        }
+    }
+}
+
+fn remove<T>(link: Link<T>, interval: &Interval, removed: &mut Option<T>) -> Link<T> {
+    let mut node = link?;
+
+    match key(interval).cmp(&key(&node.interval)) {
+        Ordering::Less => node.left = remove(node.left.take(), interval, removed),
+        Ordering::Greater => node.right = remove(node.right.take(), interval, removed),
+        Ordering::Equal => {
+            let (left, right) = (node.left.take(), node.right.take());
+            *removed = Some(node.value);
+            return match (left, right) {
+                (None, None) => None,
+                (Some(child), None) | (None, Some(child)) => Some(child),
+                (Some(left), Some(right)) => {
+                    let (right, mut successor) = remove_min(right);
+                    successor.left = Some(left);
+                    successor.right = right;
+                    Some(rebalance(successor))
+                }
+            };
+        }
+    }
+
+    Some(rebalance(node))
+}
+
+impl<T> Default for IntervalTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IntervalTree<T> {
+    pub fn new() -> Self {
+        IntervalTree { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, interval: Interval, value: T) {
+        self.root = Some(insert(self.root.take(), interval, value));
+        self.len += 1;
+    }
+
+    /// Removes one entry with exactly this interval and returns its payload.
+    pub fn remove(&mut self, interval: &Interval) -> Option<T> {
+        let mut removed = None;
+        self.root = remove(self.root.take(), interval, &mut removed);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Every entry in order of interval start.
+    pub fn iter(&self) -> impl Iterator<Item = (&Interval, &T)> {
+        let mut entries = Vec::with_capacity(self.len);
+        let mut stack = Vec::new();
+        let mut current = self.root.as_deref();
+
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                stack.push(node);
+                current = node.left.as_deref();
+            }
+            let node = stack.pop().unwrap();
+            entries.push((&node.interval, &node.value));
+            current = node.right.as_deref();
+        }
+
+        entries.into_iter()
+    }
+
+    /// Every entry whose interval contains `value`, in order of interval start.
+    pub fn stab(&self, value: u64) -> Vec<(&Interval, &T)> {
+        self.overlapping(&Interval::new(value, 1))
+    }
+
+    /// Every entry whose interval overlaps `query` (see [`Interval::overlaps`]), in order of
+    /// interval start.
+    pub fn overlapping(&self, query: &Interval) -> Vec<(&Interval, &T)> {
+        let mut hits = Vec::new();
+        collect_overlapping(&self.root, query, &mut hits);
+        hits
+    }
+}
+
+fn collect_overlapping<'a, T>(
+    link: &'a Link<T>,
+    query: &Interval,
+    hits: &mut Vec<(&'a Interval, &'a T)>,
+) {
+    let node = match link {
+        Some(node) if node.max > query.start => node,
+        _ => return,
+    };
+
+    collect_overlapping(&node.left, query, hits);
+
+    if node.interval.overlaps(query) {
+        hits.push((&node.interval, &node.value));
+    }
+
+    // everything to the right starts at or after this node
+    if node.interval.start < query.end() {
+        collect_overlapping(&node.right, query, hits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // returns the height so parents can check their balance
+    fn check_invariants<T>(link: &Link<T>) -> u32 {
+        match link {
+            None => 0,
+            Some(node) => {
+                let left = check_invariants(&node.left);
+                let right = check_invariants(&node.right);
+                assert!(left.abs_diff(right) <= 1, "unbalanced node");
+                assert_eq!(node.height, 1 + left.max(right));
+                assert_eq!(
+                    node.max,
+                    node.interval
+                        .end()
+                        .max(max(&node.left))
+                        .max(max(&node.right))
+                );
+                node.height
+            }
+        }
+    }
+
+    fn sorted(mut entries: Vec<(Interval, u32)>) -> Vec<(Interval, u32)> {
+        entries.sort_by_key(|(interval, value)| (key(interval), *value));
+        entries
+    }
+
+    fn collect(hits: Vec<(&Interval, &u32)>) -> Vec<(Interval, u32)> {
+        sorted(
+            hits.into_iter()
+                .map(|(interval, value)| (*interval, *value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn stab_test() {
+        let mut tree = IntervalTree::new();
+        tree.insert(Interval::new(98, 2), "a");
+        tree.insert(Interval::new(50, 48), "b");
+        tree.insert(Interval::new(60, 1), "c");
+
+        let hits = tree
+            .stab(60)
+            .into_iter()
+            .map(|(_, value)| *value)
+            .collect::<Vec<_>>();
+        assert_eq!(hits, vec!["b", "c"]);
+        assert!(tree.stab(100).is_empty());
+        // an entry on the right of a node that does not match used to be missed
+        assert_eq!(tree.stab(99).len(), 1);
+    }
+
+    #[test]
+    fn remove_test() {
+        let mut tree = IntervalTree::new();
+        tree.insert(Interval::new(0, 10), 1);
+        tree.insert(Interval::new(5, 10), 2);
+
+        assert_eq!(tree.remove(&Interval::new(0, 10)), Some(1));
+        assert_eq!(tree.remove(&Interval::new(0, 10)), None);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(collect(tree.stab(7)), vec![(Interval::new(5, 10), 2)]);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_linear_scan(
+            entries in prop::collection::vec((0u64..200, 0u64..30), 0..80),
+            removals in prop::collection::vec(any::<prop::sample::Index>(), 0..40),
+            queries in prop::collection::vec((0u64..240, 0u64..30), 1..20),
+        ) {
+            let mut tree = IntervalTree::new();
+            let mut scan = Vec::new();
+
+            for (value, &(start, width)) in entries.iter().enumerate() {
+                tree.insert(Interval::new(start, width), value as u32);
+                scan.push((Interval::new(start, width), value as u32));
+            }
+            check_invariants(&tree.root);
+
+            for removal in removals {
+                if scan.is_empty() {
+                    break;
+                }
+                let (interval, _) = scan[removal.index(scan.len())];
+                let removed = tree.remove(&interval).unwrap();
+                let position = scan.iter().position(|entry| *entry == (interval, removed)).unwrap();
+                scan.remove(position);
+                check_invariants(&tree.root);
+            }
+
+            prop_assert_eq!(tree.len(), scan.len());
+
+            for (start, width) in queries {
+                let query = Interval::new(start, width);
+                let expected = sorted(
+                    scan.iter().copied().filter(|(interval, _)| interval.overlaps(&query)).collect(),
+                );
+                prop_assert_eq!(collect(tree.overlapping(&query)), expected);
+
+                let expected = sorted(
+                    scan.iter().copied().filter(|(interval, _)| interval.contains(start)).collect(),
+                );
+                prop_assert_eq!(collect(tree.stab(start)), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,17 @@
+pub mod aho_corasick;
+pub mod boxes;
+pub mod byte_parsing;
+pub mod cycle;
+pub mod decimal;
+pub mod distinct;
+pub mod grid;
+pub mod interval;
+pub mod interval_tree;
+pub mod parsing;
+pub mod range_map;
+pub mod render;
+pub mod sparse_grid;
+pub mod table;
+
+// lets the code generated by `aoc-derive` refer to `::advent_of_code` from inside this crate
+extern crate self as advent_of_code;
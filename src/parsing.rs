@@ -0,0 +1,520 @@
+use std::fmt;
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::{is_not, tag},
+    character::complete::{char, digit1, line_ending, one_of, space0, space1},
+    combinator::{all_consuming, map_res, opt, recognize},
+    error::{ErrorKind, FromExternalError, ParseError, VerboseError, VerboseErrorKind},
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, separated_pair, terminated},
+    IResult, Offset, Parser,
+};
+
+pub use aoc_derive::AocParse;
+
+/// Digits parsed into any unsigned number type.
+pub fn unsigned<'a, T, E>(input: &'a str) -> IResult<&'a str, T, E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    map_res(digit1, str::parse)(input)
+}
+
+/// Digits with an optional `+` or `-` sign.
+pub fn signed<'a, T, E>(input: &'a str) -> IResult<&'a str, T, E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// Whitespace separated items such as `41 48 83  6`, leading whitespace is skipped.
+pub fn number_list<'a, O, E, F>(number: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    preceded(space0, separated_list1(space1, number))
+}
+
+/// A `Card 12:` style prefix, returning the number.
+pub fn labelled<'a, T, E>(label: &'a str) -> impl FnMut(&'a str) -> IResult<&'a str, T, E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    delimited(pair(tag(label), space1), unsigned, pair(char(':'), space0))
+}
+
+/// A `key: value` record, the key is everything up to the colon.
+pub fn key_value<'a, O, E, F>(value: F) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O), E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_pair(is_not(":\r\n"), pair(char(':'), space0), value)
+}
+
+/// Items separated by `,` and optional spaces.
+pub fn comma_list<'a, O, E, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(terminated(char(','), space0), item)
+}
+
+/// Items separated by `;` and optional spaces.
+pub fn semicolon_list<'a, O, E, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(terminated(char(';'), space0), item)
+}
+
+/// One item per line.
+pub fn lines<'a, O, E, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(line_ending, item)
+}
+
+/// Blocks separated by a blank line.
+pub fn blocks<'a, O, E, F>(block: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(pair(line_ending, line_ending), block)
+}
+
+/// A parse failure located in its source, displayed compiler style with the offending line and
+/// a caret under the error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    pub source_line: String,
+    pub expected: String,
+}
+
+impl Diagnostic {
+    /// Locates the byte `offset` of `source`.
+    pub fn at(file: &str, source: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let offset = offset.min(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |index| offset + index);
+
+        Diagnostic {
+            file: file.to_string(),
+            line: source[..line_start].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            source_line: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Converts the error of a parser that ran on `source` or on any slice of it.
+    pub fn from_nom(file: &str, source: &str, error: nom::Err<VerboseError<&str>>) -> Self {
+        match error {
+            nom::Err::Incomplete(_) => Diagnostic::at(file, source, source.len(), "more input"),
+            nom::Err::Error(error) | nom::Err::Failure(error) => {
+                let Some((position, kind)) = error.errors.first() else {
+                    return Diagnostic::at(file, source, 0, "valid input");
+                };
+                // the innermost error points at the failure, an enclosing context names it best
+                // when the innermost one is a bare nom kind
+                let context = error.errors.iter().find_map(|(_, kind)| match kind {
+                    VerboseErrorKind::Context(context) => Some(*context),
+                    _ => None,
+                });
+                let expected = match (kind, context) {
+                    (VerboseErrorKind::Char(c), _) => format!("`{}`", c.escape_debug()),
+                    (VerboseErrorKind::Context(context), _) => context.to_string(),
+                    (VerboseErrorKind::Nom(ErrorKind::Eof), _) => "end of line".to_string(),
+                    (VerboseErrorKind::Nom(_), Some(context)) => context.to_string(),
+                    (VerboseErrorKind::Nom(kind), None) => kind.description().to_lowercase(),
+                };
+                Diagnostic::at(file, source, source.offset(position), expected)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let indent: String = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        writeln!(f, "error: expected {}", self.expected)?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter, self.file, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}^", gutter, indent)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Parses every line of `source` with `item`, which has to consume the whole line. `file` is only
+/// used to label a failure.
+pub fn parse_lines<'a, O, F>(file: &str, source: &'a str, item: F) -> Result<Vec<O>, Diagnostic>
+where
+    F: Parser<&'a str, O, VerboseError<&'a str>>,
+{
+    let mut item = all_consuming(item);
+    source
+        .lines()
+        .map(|line| {
+            item.parse(line)
+                .map(|(_, output)| output)
+                .map_err(|error| Diagnostic::from_nom(file, source, error))
+        })
+        .collect()
+}
+
+/// Parses every line of `source` into a type whose parse errors are [`FieldError`]s, such as an
+/// [`AocParse`](aoc_derive::AocParse) struct. `file` is only used to label a failure.
+pub fn parse_records<T>(file: &str, source: &str) -> Result<Vec<T>, Diagnostic>
+where
+    T: FromStr<Err = FieldError>,
+{
+    source
+        .lines()
+        .map(|line| {
+            line.parse().map_err(|error: FieldError| {
+                let offset = source.offset(line) + error.offset;
+                Diagnostic::at(file, source, offset, error.expected)
+            })
+        })
+        .collect()
+}
+
+/// Shows either the answer or the error, usually a [`Diagnostic`], for puzzles whose solvers can
+/// fail.
+pub fn report<T: fmt::Display, E: fmt::Display>(result: Result<T, E>) -> String {
+    match result {
+        Ok(answer) => answer.to_string(),
+        Err(error) => format!("\n{}", error),
+    }
+}
+
+/// A value that can be parsed from the start of a line, used for the fields of
+/// [`AocParse`](aoc_derive::AocParse) structs. Numbers and words are single whitespace free
+/// tokens, a `Vec` is a whitespace separated list.
+pub trait FromInput: Sized {
+    fn from_input(input: &str) -> IResult<&str, Self>;
+}
+
+macro_rules! impl_from_input_unsigned {
+    ($($t:ty),*) => {
+        $(impl FromInput for $t {
+            fn from_input(input: &str) -> IResult<&str, Self> {
+                unsigned(input)
+            }
+        })*
+    };
+}
+
+macro_rules! impl_from_input_signed {
+    ($($t:ty),*) => {
+        $(impl FromInput for $t {
+            fn from_input(input: &str) -> IResult<&str, Self> {
+                signed(input)
+            }
+        })*
+    };
+}
+
+impl_from_input_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_input_signed!(i8, i16, i32, i64, i128, isize);
+
+impl FromInput for String {
+    fn from_input(input: &str) -> IResult<&str, Self> {
+        is_not(" \t\r\n").map(str::to_string).parse(input)
+    }
+}
+
+impl FromInput for char {
+    fn from_input(input: &str) -> IResult<&str, Self> {
+        nom::character::complete::satisfy(|c| !c.is_whitespace())(input)
+    }
+}
+
+impl<T: FromInput> FromInput for Vec<T> {
+    fn from_input(input: &str) -> IResult<&str, Self> {
+        separated_list1(space1, T::from_input)(input)
+    }
+}
+
+/// The field of a derived parser that did not match, `offset` is in bytes from the start of
+/// the parsed text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    pub type_name: &'static str,
+    /// Empty when the failure is outside of any field, e.g. in the prefix.
+    pub field: &'static str,
+    pub expected: String,
+    pub offset: usize,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.field.is_empty() {
+            write!(f, "{}", self.type_name)?;
+        } else {
+            write!(f, "{}.{}", self.type_name, self.field)?;
+        }
+        write!(f, ": expected {} at offset {}", self.expected, self.offset)
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Helpers for the code generated by [`AocParse`](aoc_derive::AocParse).
+#[doc(hidden)]
+pub mod support {
+    use super::FromInput;
+
+    pub use nom::IResult;
+
+    fn skip_spaces(input: &str) -> &str {
+        input.trim_start_matches([' ', '\t'])
+    }
+
+    fn is_word(c: Option<char>) -> bool {
+        c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    /// Skips spaces and `literal`, on failure returns where the literal was expected. A literal
+    /// that ends in a word character has to end a word, so `Card` does not match `Cards`.
+    pub fn literal<'a>(input: &'a str, literal: &str) -> Result<&'a str, &'a str> {
+        let input = skip_spaces(input);
+        match input.strip_prefix(literal) {
+            Some(rest) if !(is_word(literal.chars().last()) && is_word(rest.chars().next())) => {
+                Ok(rest)
+            }
+            _ => Err(input),
+        }
+    }
+
+    pub fn field<T: FromInput>(input: &str) -> Result<(&str, T), &str> {
+        let input = skip_spaces(input);
+        T::from_input(input).map_err(|_| input)
+    }
+
+    pub fn nom_error(input: &str, offset: usize) -> nom::Err<nom::error::Error<&str>> {
+        nom::Err::Error(nom::error::Error::new(
+            &input[offset..],
+            nom::error::ErrorKind::Verify,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{
+        error::{context, Error},
+        sequence::tuple,
+    };
+
+    type Result<'a, O> = IResult<&'a str, O, Error<&'a str>>;
+
+    #[test]
+    fn number_list_test() {
+        let result: Result<Vec<u32>> = number_list(unsigned)("  83 86  6 | 1");
+
+        assert_eq!(result, Ok((" | 1", vec![83, 86, 6])));
+    }
+
+    #[test]
+    fn signed_test() {
+        let result: Result<Vec<i64>> = comma_list(signed)("-3, +4,5");
+
+        assert_eq!(result, Ok(("", vec![-3, 4, 5])));
+        assert!(signed::<i64, Error<&str>>("-").is_err());
+    }
+
+    #[test]
+    fn labelled_test() {
+        let result: Result<u32> = labelled("Card")("Card   12:  41 48");
+
+        assert_eq!(result, Ok(("41 48", 12)));
+        assert!(labelled::<u32, Error<&str>>("Game")("Card 1:").is_err());
+    }
+
+    #[test]
+    fn key_value_test() {
+        let result: Result<(&str, Vec<u64>)> =
+            key_value(number_list(unsigned))("Time:      7  15   30");
+
+        assert_eq!(result, Ok(("", ("Time", vec![7, 15, 30]))));
+    }
+
+    #[test]
+    fn semicolon_list_test() {
+        let result: Result<Vec<Vec<u32>>> = semicolon_list(comma_list(unsigned))("1, 2; 3;4");
+
+        assert_eq!(result, Ok(("", vec![vec![1, 2], vec![3], vec![4]])));
+    }
+
+    #[test]
+    fn blocks_test() {
+        let result: Result<Vec<Vec<u32>>> = blocks(lines(unsigned))("1\n2\n\n3\n");
+
+        assert_eq!(result, Ok(("\n", vec![vec![1, 2], vec![3]])));
+    }
+
+    #[test]
+    fn diagnostic_test() {
+        let source = "Card 1: 41 48 | 83\nCard 2: 13 32 / 61";
+        let card = |input| {
+            tuple((
+                labelled::<u32, _>("Card"),
+                number_list(unsigned::<u32, _>),
+                preceded(space0, char('|')),
+                number_list(unsigned::<u32, _>),
+            ))(input)
+        };
+
+        let diagnostic = parse_lines("day4.txt", source, card).unwrap_err();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 15));
+        assert_eq!(diagnostic.expected, "`|`");
+        assert_eq!(
+            diagnostic.to_string(),
+            [
+                "error: expected `|`",
+                " --> day4.txt:2:15",
+                "  |",
+                "2 | Card 2: 13 32 / 61",
+                "  |               ^",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn diagnostic_context_test() {
+        let source = "Game 1 x";
+        let game =
+            |input| context("a game number", preceded(tag("Game "), unsigned::<u32, _>))(input);
+
+        let diagnostic = parse_lines("day2.txt", source, game).unwrap_err();
+        assert_eq!(
+            (diagnostic.column, diagnostic.expected.as_str()),
+            (7, "end of line")
+        );
+
+        let diagnostic = parse_lines("day2.txt", "Game x", game).unwrap_err();
+        assert_eq!(
+            (diagnostic.column, diagnostic.expected.as_str()),
+            (6, "a game number")
+        );
+    }
+
+    #[derive(AocParse, Debug, PartialEq)]
+    #[aoc(prefix = "Card", sep = "|")]
+    struct Card {
+        id: u32,
+        #[aoc(before = ":")]
+        winning_numbers: Vec<u32>,
+        scratch_numbers: Vec<u32>,
+    }
+
+    #[derive(AocParse, Debug, PartialEq)]
+    struct Hand {
+        cards: String,
+        bid: u32,
+    }
+
+    #[test]
+    fn derive_card_test() {
+        let card: Card = "Card 1: 41 48 83 | 83 86  6".parse().unwrap();
+
+        assert_eq!(
+            card,
+            Card {
+                id: 1,
+                winning_numbers: vec![41, 48, 83],
+                scratch_numbers: vec![83, 86, 6],
+            }
+        );
+    }
+
+    #[test]
+    fn derive_hand_test() {
+        assert_eq!(
+            "32T3K 765".parse::<Hand>(),
+            Ok(Hand {
+                cards: "32T3K".to_string(),
+                bid: 765,
+            })
+        );
+    }
+
+    #[test]
+    fn derive_error_test() {
+        let error = "Card 1: 41 48 / 83".parse::<Card>().unwrap_err();
+
+        assert_eq!(error.field, "scratch_numbers");
+        assert_eq!(error.expected, "`|`");
+        assert_eq!(error.offset, 14);
+        assert_eq!(
+            error.to_string(),
+            "Card.scratch_numbers: expected `|` at offset 14"
+        );
+
+        let error = "Card x: 1 | 2".parse::<Card>().unwrap_err();
+        assert_eq!((error.field, error.expected.as_str()), ("id", "u32"));
+
+        for line in ["Cards 1: 1 | 2", "Cardboard 1: 1 | 2"] {
+            let error = line.parse::<Card>().unwrap_err();
+            assert_eq!((error.field, error.offset), ("", 0));
+        }
+    }
+
+    #[test]
+    fn parse_records_test() {
+        let source = "Card 1: 41 | 83\nCard 2: 13 / 61";
+        let diagnostic = parse_records::<Card>("day4.txt", source).unwrap_err();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 12));
+        assert_eq!(diagnostic.expected, "`|`");
+        assert_eq!(
+            parse_records::<Card>("day4.txt", "Card 1: 41 | 83").unwrap()[0].id,
+            1
+        );
+    }
+
+    #[test]
+    fn derive_nested_test() {
+        let (rest, hands) = lines(Hand::from_input)("AAAAA 1\nKK677 28").unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(hands[1].bid, 28);
+    }
+}
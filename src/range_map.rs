@@ -0,0 +1,280 @@
+use crate::interval::{Interval, IntervalSet};
+
+/// A piecewise shift of `u64` values: each segment moves its source interval so that it starts
+/// at `target`, values outside every segment map to themselves.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeMap {
+    // sorted by source start, never overlapping and never the identity
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub source: Interval,
+    pub target: u64,
+}
+
+impl Segment {
+    fn is_identity(&self) -> bool {
+        self.source.start == self.target
+    }
+
+    fn apply(&self, value: u64) -> u64 {
+        self.target + (value - self.source.start)
+    }
+
+    fn restrict(&self, interval: Interval) -> Segment {
+        Segment {
+            source: interval,
+            target: self.apply(interval.start),
+        }
+    }
+}
+
+impl RangeMap {
+    /// The identity map.
+    pub fn new() -> Self {
+        RangeMap::default()
+    }
+
+    /// Builds a map from `(source, target)` rules. Where rules overlap the first one wins.
+    pub fn from_rules(rules: impl IntoIterator<Item = (Interval, u64)>) -> Self {
+        let mut covered = IntervalSet::new();
+        let mut segments = Vec::new();
+
+        for (source, target) in rules {
+            let rule = Segment { source, target };
+            let uncovered = [source]
+                .into_iter()
+                .collect::<IntervalSet>()
+                .difference(&covered);
+            for interval in uncovered.intervals() {
+                segments.push(rule.restrict(*interval));
+            }
+            covered.insert(source);
+        }
+
+        RangeMap::from_segments(segments)
+    }
+
+    fn from_segments(mut segments: Vec<Segment>) -> Self {
+        segments.retain(|segment| !segment.is_identity() && !segment.source.is_empty());
+        segments.sort_by_key(|segment| segment.source.start);
+
+        let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            match merged.last_mut() {
+                Some(last)
+                    if last.source.end() == segment.source.start
+                        && last.target + last.source.width == segment.target =>
+                {
+                    last.source.width += segment.source.width;
+                }
+                _ => merged.push(segment),
+            }
+        }
+
+        RangeMap { segments: merged }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    fn segment_containing(&self, value: u64) -> Option<&Segment> {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.source.end() <= value);
+        self.segments
+            .get(index)
+            .filter(|segment| segment.source.contains(value))
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        match self.segment_containing(value) {
+            Some(segment) => segment.apply(value),
+            None => value,
+        }
+    }
+
+    /// Every segment including the identity gaps between them, covering `0..u64::MAX`.
+    fn pieces(&self) -> Vec<Segment> {
+        let mut pieces = Vec::with_capacity(self.segments.len() * 2 + 1);
+        let mut start = 0;
+
+        for segment in &self.segments {
+            if segment.source.start > start {
+                pieces.push(Segment {
+                    source: Interval::from_bounds(start, segment.source.start),
+                    target: start,
+                });
+            }
+            pieces.push(*segment);
+            start = segment.source.end();
+        }
+
+        if start < u64::MAX {
+            pieces.push(Segment {
+                source: Interval::from_bounds(start, u64::MAX),
+                target: start,
+            });
+        }
+
+        pieces
+    }
+
+    pub fn apply_set(&self, values: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+
+        for piece in self.pieces() {
+            let domain: IntervalSet = [piece.source].into_iter().collect();
+            for interval in values.intersection(&domain).intervals() {
+                result.push(Interval::new(piece.apply(interval.start), interval.width));
+            }
+        }
+
+        result.into_iter().collect()
+    }
+
+    /// Every value that `apply` maps into `values`.
+    pub fn preimage(&self, values: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+
+        for piece in self.pieces() {
+            let image: IntervalSet = [Interval::new(piece.target, piece.source.width)]
+                .into_iter()
+                .collect();
+            for interval in values.intersection(&image).intervals() {
+                result.push(Interval::new(
+                    piece.source.start + (interval.start - piece.target),
+                    interval.width,
+                ));
+            }
+        }
+
+        result.into_iter().collect()
+    }
+
+    /// The map that applies `self` first and `other` second.
+    pub fn compose(&self, other: &RangeMap) -> RangeMap {
+        let other_pieces = other.pieces();
+        let mut segments = Vec::new();
+
+        for piece in self.pieces() {
+            let image = Interval::new(piece.target, piece.source.width);
+            let first = other_pieces.partition_point(|other| other.source.end() <= image.start);
+
+            for other_piece in &other_pieces[first..] {
+                let Some(intersect) = image.intersect(&other_piece.source) else {
+                    break;
+                };
+                segments.push(Segment {
+                    source: Interval::new(
+                        piece.source.start + (intersect.start - image.start),
+                        intersect.width,
+                    ),
+                    target: other_piece.apply(intersect.start),
+                });
+            }
+        }
+
+        RangeMap::from_segments(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed_to_soil() -> RangeMap {
+        RangeMap::from_rules([(Interval::new(98, 2), 50), (Interval::new(50, 48), 52)])
+    }
+
+    fn soil_to_fertilizer() -> RangeMap {
+        RangeMap::from_rules([
+            (Interval::new(15, 37), 0),
+            (Interval::new(52, 2), 37),
+            (Interval::new(0, 15), 39),
+        ])
+    }
+
+    #[test]
+    fn apply_test() {
+        let map = seed_to_soil();
+
+        assert_eq!(map.apply(79), 81);
+        assert_eq!(map.apply(14), 14);
+        assert_eq!(map.apply(99), 51);
+        assert_eq!(map.apply(100), 100);
+    }
+
+    #[test]
+    fn first_rule_wins_test() {
+        let map = RangeMap::from_rules([(Interval::new(0, 10), 100), (Interval::new(5, 10), 200)]);
+
+        assert_eq!(map.apply(7), 107);
+        assert_eq!(map.apply(12), 207);
+    }
+
+    #[test]
+    fn apply_set_test() {
+        let seeds: IntervalSet = [Interval::new(90, 10)].into_iter().collect();
+
+        assert_eq!(
+            seed_to_soil().apply_set(&seeds),
+            [Interval::new(50, 2), Interval::new(92, 8)]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn preimage_test() {
+        let map = seed_to_soil().compose(&soil_to_fertilizer());
+        let values: IntervalSet = [Interval::new(0, 10), Interval::new(50, 5)]
+            .into_iter()
+            .collect();
+        let preimage = map.preimage(&values);
+
+        for value in 0..200 {
+            assert_eq!(
+                preimage.contains(value),
+                values.contains(map.apply(value)),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn compose_test() {
+        let first = seed_to_soil();
+        let second = soil_to_fertilizer();
+        let composed = first.compose(&second);
+
+        for value in 0..200 {
+            assert_eq!(
+                composed.apply(value),
+                second.apply(first.apply(value)),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn compose_identity_test() {
+        let map = soil_to_fertilizer();
+
+        assert_eq!(RangeMap::new().compose(&map), map);
+        assert_eq!(map.compose(&RangeMap::new()), map);
+    }
+
+    #[test]
+    fn compose_cancelling_test() {
+        let forward =
+            RangeMap::from_rules([(Interval::new(0, 10), 10), (Interval::new(10, 10), 0)]);
+
+        assert_eq!(forward.compose(&forward), RangeMap::new());
+    }
+}
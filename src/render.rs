@@ -0,0 +1,351 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::grid::GridView;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// Renders a grid as text with 24 bit ANSI colours, drawing the cells where `marked` holds in
+/// reverse video.
+pub fn to_ansi<G: GridView>(
+    grid: &G,
+    empty: char,
+    glyph: impl Fn(&G::Cell) -> char,
+    palette: impl Fn(&G::Cell) -> Rgb,
+    marked: impl Fn(i64, i64) -> bool,
+) -> String {
+    let mut output = String::new();
+
+    if let Some(((min_x, min_y), (max_x, max_y))) = grid.bounds() {
+        for y in min_y..=max_y {
+            if y > min_y {
+                output.push('\n');
+            }
+            for x in min_x..=max_x {
+                if marked(x, y) {
+                    output.push_str("\x1b[7m");
+                }
+                match grid.cell(x, y) {
+                    Some(cell) => {
+                        let Rgb(r, g, b) = palette(cell);
+                        output.push_str(&format!("\x1b[38;2;{};{};{}m{}", r, g, b, glyph(cell)));
+                    }
+                    None => output.push(empty),
+                }
+                output.push_str("\x1b[0m");
+            }
+        }
+    }
+
+    output
+}
+
+/// An RGB raster, every grid cell becomes a `scale` by `scale` block of pixels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<Rgb>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageError {
+    /// The grid scaled up by `scale` is more than `u32::MAX` pixels wide or high.
+    TooLarge { scale: u32 },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::TooLarge { scale } => {
+                write!(f, "grid is too large to render at scale {}", scale)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+// pixels along one side of the bounds `min..=max`, `None` when they do not fit into a u32
+fn scaled_side(min: i64, max: i64, scale: u32) -> Option<u32> {
+    let cells = max.checked_sub(min)?.checked_add(1)?;
+    u32::try_from(cells).ok()?.checked_mul(scale)
+}
+
+impl Image {
+    pub fn from_grid<G: GridView>(
+        grid: &G,
+        scale: u32,
+        empty: Rgb,
+        palette: impl Fn(&G::Cell) -> Rgb,
+    ) -> Result<Self, ImageError> {
+        let ((min_x, min_y), (max_x, max_y)) = match grid.bounds() {
+            Some(bounds) => bounds,
+            None => {
+                return Ok(Self {
+                    width: 0,
+                    height: 0,
+                    pixels: Vec::new(),
+                })
+            }
+        };
+
+        let too_large = ImageError::TooLarge { scale };
+        let width = scaled_side(min_x, max_x, scale).ok_or(too_large)?;
+        let height = scaled_side(min_y, max_y, scale).ok_or(too_large)?;
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+
+        for py in 0..height {
+            for px in 0..width {
+                let x = min_x + (px / scale) as i64;
+                let y = min_y + (py / scale) as i64;
+                pixels.push(grid.cell(x, y).map_or(empty, &palette));
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn write(&self, format: ImageFormat, writer: impl Write) -> io::Result<()> {
+        match format {
+            ImageFormat::Ppm => self.write_ppm(writer),
+            ImageFormat::Png => self.write_png(writer),
+        }
+    }
+
+    /// Binary (P6) portable pixmap.
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for Rgb(r, g, b) in &self.pixels {
+            writer.write_all(&[*r, *g, *b])?;
+        }
+        writer.flush()
+    }
+
+    /// Truecolour PNG, the image data is stored in uncompressed deflate blocks.
+    pub fn write_png(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8 bit depth, truecolour, deflate, adaptive filtering, no interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_png_chunk(&mut writer, b"IHDR", &header)?;
+
+        let mut scanlines =
+            Vec::with_capacity((self.width as usize * 3 + 1) * self.height as usize);
+        for row in self.pixels.chunks(self.width.max(1) as usize) {
+            scanlines.push(0);
+            for Rgb(r, g, b) in row {
+                scanlines.extend_from_slice(&[*r, *g, *b]);
+            }
+        }
+        write_png_chunk(&mut writer, b"IDAT", &zlib_stored(&scanlines))?;
+        write_png_chunk(&mut writer, b"IEND", &[])?;
+        writer.flush()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>, format: ImageFormat) -> io::Result<()> {
+        self.write(format, BufWriter::new(File::create(path)?))
+    }
+}
+
+fn write_png_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data.iter()));
+    writer.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        output.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let length = block.len() as u16;
+        output.push(if blocks.peek().is_none() { 1 } else { 0 });
+        output.extend_from_slice(&length.to_le_bytes());
+        output.extend_from_slice(&(!length).to_le_bytes());
+        output.extend_from_slice(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    output.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    output
+}
+
+/// Writes numbered images (`frame_00000.png`, ...) into a directory, one per simulation step.
+pub struct FrameDumper {
+    directory: PathBuf,
+    format: ImageFormat,
+    next_frame: usize,
+}
+
+impl FrameDumper {
+    pub fn new(directory: impl Into<PathBuf>, format: ImageFormat) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            format,
+            next_frame: 0,
+        })
+    }
+
+    pub fn push(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.directory.join(format!(
+            "frame_{:05}.{}",
+            self.next_frame,
+            self.format.extension()
+        ));
+        image.save(&path, self.format)?;
+        self.next_frame += 1;
+        Ok(path)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.next_frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::sparse_grid::SparseGrid;
+
+    fn palette(cell: &char) -> Rgb {
+        match cell {
+            '#' => Rgb::WHITE,
+            _ => Rgb(255, 0, 0),
+        }
+    }
+
+    #[test]
+    fn ansi_test() {
+        let grid = Grid::parse("#.", |c| c).unwrap();
+        let output = to_ansi(&grid, ' ', |c| *c, palette, |x, _| x == 1);
+
+        assert_eq!(
+            output,
+            "\x1b[38;2;255;255;255m#\x1b[0m\x1b[7m\x1b[38;2;255;0;0m.\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn ppm_test() {
+        let mut grid = SparseGrid::new();
+        grid.insert(-1, 0, '#');
+        grid.insert(1, 0, '.');
+        let image = Image::from_grid(&grid, 1, Rgb::BLACK, palette).unwrap();
+
+        let mut output = Vec::new();
+        image.write_ppm(&mut output).unwrap();
+
+        assert_eq!(
+            output,
+            b"P6\n3 1\n255\n\xff\xff\xff\x00\x00\x00\xff\x00\x00".to_vec()
+        );
+    }
+
+    #[test]
+    fn too_large_test() {
+        let mut grid = SparseGrid::new();
+        grid.insert(0, 0, '#');
+        grid.insert(70_000, 0, '#');
+
+        let image = Image::from_grid(&grid, 70_000, Rgb::BLACK, palette);
+        assert_eq!(image, Err(ImageError::TooLarge { scale: 70_000 }));
+
+        grid.insert(i64::MIN, 0, '#');
+        assert!(Image::from_grid(&grid, 1, Rgb::BLACK, palette).is_err());
+    }
+
+    #[test]
+    fn png_test() {
+        let grid = Grid::parse("#.\n.#", |c| c).unwrap();
+        let image = Image::from_grid(&grid, 2, Rgb::BLACK, palette).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 4));
+
+        let mut output = Vec::new();
+        image.write_png(&mut output).unwrap();
+
+        assert!(output.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert!(output.ends_with(b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn frame_dumper_test() {
+        let directory = std::env::temp_dir().join(format!("aoc_frames_{}", std::process::id()));
+        let mut frames = FrameDumper::new(&directory, ImageFormat::Ppm).unwrap();
+        let image =
+            Image::from_grid(&Grid::parse("#", |c| c).unwrap(), 1, Rgb::BLACK, palette).unwrap();
+
+        frames.push(&image).unwrap();
+        let path = frames.push(&image).unwrap();
+
+        assert_eq!(frames.frame_count(), 2);
+        assert!(path.ends_with("frame_00001.ppm"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use crate::grid::GridView;
+
+type Bounds<P> = Option<(P, P)>;
+
+/// An unbounded grid keyed by signed `(x, y)` coordinates that grows in every direction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SparseGrid<T> {
+    cells: BTreeMap<(i64, i64), T>,
+    bounds: Bounds<(i64, i64)>,
+}
+
+/// The three dimensional version of [`SparseGrid`], keyed by `(x, y, z)`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SparseGrid3<T> {
+    cells: BTreeMap<(i64, i64, i64), T>,
+    bounds: Bounds<(i64, i64, i64)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: BTreeMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        self.cells.get(&(x, y))
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        self.cells.get_mut(&(x, y))
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.cells.contains_key(&(x, y))
+    }
+
+    pub fn insert(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => ((x, y), (x, y)),
+            Some(((min_x, min_y), (max_x, max_y))) => {
+                ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+            }
+        });
+        self.cells.insert((x, y), value)
+    }
+
+    /// Removes a cell, shrinking the bounds when the cell was on their edge.
+    pub fn remove(&mut self, x: i64, y: i64) -> Option<T> {
+        let removed = self.cells.remove(&(x, y));
+
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds {
+            if removed.is_some() && (x == min_x || x == max_x || y == min_y || y == max_y) {
+                self.bounds = self.cells.keys().fold(None, |bounds, &(x, y)| {
+                    Some(match bounds {
+                        None => ((x, y), (x, y)),
+                        Some(((min_x, min_y), (max_x, max_y))) => {
+                            ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+                        }
+                    })
+                });
+            }
+        }
+
+        removed
+    }
+
+    /// Cells in `(y, x)` reading order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        let mut cells = self
+            .cells
+            .iter()
+            .map(|(&position, value)| (position, value))
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|&((x, y), _)| (y, x));
+        cells.into_iter()
+    }
+}
+
+impl<T> GridView for SparseGrid<T> {
+    type Cell = T;
+
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        self.bounds
+    }
+
+    fn cell(&self, x: i64, y: i64) -> Option<&T> {
+        self.get(x, y)
+    }
+}
+
+impl<T> Default for SparseGrid3<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid3<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: BTreeMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Bounds<(i64, i64, i64)> {
+        self.bounds
+    }
+
+    pub fn get(&self, x: i64, y: i64, z: i64) -> Option<&T> {
+        self.cells.get(&(x, y, z))
+    }
+
+    pub fn insert(&mut self, x: i64, y: i64, z: i64, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => ((x, y, z), (x, y, z)),
+            Some(((min_x, min_y, min_z), (max_x, max_y, max_z))) => (
+                (min_x.min(x), min_y.min(y), min_z.min(z)),
+                (max_x.max(x), max_y.max(y), max_z.max(z)),
+            ),
+        });
+        self.cells.insert((x, y, z), value)
+    }
+
+    pub fn remove(&mut self, x: i64, y: i64, z: i64) -> Option<T> {
+        let removed = self.cells.remove(&(x, y, z));
+
+        if removed.is_some() {
+            self.bounds = self.cells.keys().fold(None, |bounds, &(x, y, z)| {
+                Some(match bounds {
+                    None => ((x, y, z), (x, y, z)),
+                    Some(((min_x, min_y, min_z), (max_x, max_y, max_z))) => (
+                        (min_x.min(x), min_y.min(y), min_z.min(z)),
+                        (max_x.max(x), max_y.max(y), max_z.max(z)),
+                    ),
+                })
+            });
+        }
+
+        removed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64, i64), &T)> {
+        self.cells
+            .iter()
+            .map(|(&position, value)| (position, value))
+    }
+}
+
+impl<T: Clone> SparseGrid3<T> {
+    /// The `z` layer as a 2D grid, e.g. for rendering.
+    pub fn layer(&self, z: i64) -> SparseGrid<T> {
+        let mut layer = SparseGrid::new();
+        for (&(x, y, cell_z), value) in &self.cells {
+            if cell_z == z {
+                layer.insert(x, y, value.clone());
+            }
+        }
+        layer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_test() {
+        let mut grid = SparseGrid::new();
+        grid.insert(0, 0, '#');
+        grid.insert(-3, 2, '#');
+        grid.insert(4, -1, '#');
+
+        assert_eq!(grid.bounds(), Some(((-3, -1), (4, 2))));
+
+        grid.remove(-3, 2);
+        assert_eq!(grid.bounds(), Some(((0, -1), (4, 0))));
+
+        grid.remove(0, 0);
+        grid.remove(4, -1);
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn render_test() {
+        let mut grid = SparseGrid::new();
+        grid.insert(-1, -1, 'H');
+        grid.insert(1, 0, 'T');
+
+        assert_eq!(grid.render('.', |c| *c), ["H..", "..T"].join("\n"));
+    }
+
+    #[test]
+    fn layer_test() {
+        let mut grid = SparseGrid3::new();
+        grid.insert(0, 0, 0, '#');
+        grid.insert(1, 1, 1, '#');
+        grid.insert(-1, 1, 1, '#');
+
+        assert_eq!(grid.bounds(), Some(((-1, 0, 0), (1, 1, 1))));
+        assert_eq!(grid.layer(1).render('.', |c| *c), "#.#");
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Rows of whitespace aligned cells behind a `Label:`, like
+///
+/// ```text
+/// Time:      7  15   30
+/// Distance:  9  40  200
+/// ```
+///
+/// Columns are read as tuples with one element per selected row, or each row can be read as a
+/// single number with the spaces between its cells removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table<'a> {
+    rows: Vec<(&'a str, Vec<&'a str>)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TableError {
+    MissingLabel(String),
+    UnknownRow(String),
+    RaggedRow {
+        label: String,
+        expected: usize,
+        found: usize,
+    },
+    WrongRowCount {
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        label: String,
+        cell: String,
+    },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::MissingLabel(line) => write!(f, "row without a `label:` {:?}", line),
+            TableError::UnknownRow(label) => write!(f, "no row labelled {:?}", label),
+            TableError::RaggedRow {
+                label,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {:?} has {} cells, expected {}",
+                label, found, expected
+            ),
+            TableError::WrongRowCount { expected, found } => {
+                write!(f, "selected {} rows for a tuple of {}", found, expected)
+            }
+            TableError::InvalidCell { label, cell } => {
+                write!(f, "invalid cell {:?} in row {:?}", cell, label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// A tuple built from one cell per row, implemented for tuples of up to six [`FromStr`] types.
+pub trait Columns: Sized {
+    const ARITY: usize;
+
+    /// Parses `cells[i]` into the `i`th element, or returns the index of the cell that failed.
+    fn from_cells(cells: &[&str]) -> Result<Self, usize>;
+}
+
+macro_rules! impl_columns {
+    ($arity:literal; $($t:ident $index:tt),*) => {
+        impl<$($t: FromStr),*> Columns for ($($t,)*) {
+            const ARITY: usize = $arity;
+
+            fn from_cells(cells: &[&str]) -> Result<Self, usize> {
+                Ok(($(cells[$index].parse::<$t>().map_err(|_| $index as usize)?,)*))
+            }
+        }
+    };
+}
+
+impl_columns!(1; A 0);
+impl_columns!(2; A 0, B 1);
+impl_columns!(3; A 0, B 1, C 2);
+impl_columns!(4; A 0, B 1, C 2, D 3);
+impl_columns!(5; A 0, B 1, C 2, D 3, E 4);
+impl_columns!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+impl<'a> Table<'a> {
+    /// Every non-empty line must be a labelled row, all rows need the same number of cells.
+    pub fn parse(input: &'a str) -> Result<Self, TableError> {
+        let rows = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (label, cells) = line
+                    .split_once(':')
+                    .ok_or_else(|| TableError::MissingLabel(line.to_string()))?;
+                Ok((label.trim(), cells.split_whitespace().collect::<Vec<_>>()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some((_, first)) = rows.first() {
+            let expected = first.len();
+            if let Some((label, cells)) = rows.iter().find(|(_, cells)| cells.len() != expected) {
+                return Err(TableError::RaggedRow {
+                    label: label.to_string(),
+                    expected,
+                    found: cells.len(),
+                });
+            }
+        }
+
+        Ok(Table { rows })
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.rows.iter().map(|(label, _)| *label)
+    }
+
+    /// Number of cells in every row.
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |(_, cells)| cells.len())
+    }
+
+    fn row(&self, label: &str) -> Result<&[&'a str], TableError> {
+        self.rows
+            .iter()
+            .find(|(row_label, _)| *row_label == label)
+            .map(|(_, cells)| cells.as_slice())
+            .ok_or_else(|| TableError::UnknownRow(label.to_string()))
+    }
+
+    fn select<C: Columns>(&self, labels: &[&str]) -> Result<Vec<&[&'a str]>, TableError> {
+        if labels.len() != C::ARITY {
+            return Err(TableError::WrongRowCount {
+                expected: C::ARITY,
+                found: labels.len(),
+            });
+        }
+        labels.iter().map(|label| self.row(label)).collect()
+    }
+
+    /// The columns of the rows named by `labels`, the first label fills the first tuple element.
+    pub fn columns<C: Columns>(&self, labels: &[&str]) -> Result<Vec<C>, TableError> {
+        let rows = self.select::<C>(labels)?;
+
+        (0..self.width())
+            .map(|column| {
+                let cells = rows.iter().map(|row| row[column]).collect::<Vec<_>>();
+                C::from_cells(&cells).map_err(|row| TableError::InvalidCell {
+                    label: labels[row].to_string(),
+                    cell: cells[row].to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Each row named by `labels` read as one number, ignoring the spaces between its cells, so
+    /// `Time: 7  15   30` is `71530`.
+    pub fn concatenated<C: Columns>(&self, labels: &[&str]) -> Result<C, TableError> {
+        let joined = self
+            .select::<C>(labels)?
+            .iter()
+            .map(|row| row.concat())
+            .collect::<Vec<_>>();
+        let cells = joined.iter().map(String::as_str).collect::<Vec<_>>();
+
+        C::from_cells(&cells).map_err(|row| TableError::InvalidCell {
+            label: labels[row].to_string(),
+            cell: joined[row].clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RACES: &str = "Time:      7  15   30\nDistance:  9  40  200\n";
+
+    #[test]
+    fn columns_test() {
+        let table = Table::parse(RACES).unwrap();
+
+        assert_eq!(table.labels().collect::<Vec<_>>(), ["Time", "Distance"]);
+        assert_eq!(
+            table.columns::<(u32, u32)>(&["Time", "Distance"]),
+            Ok(vec![(7, 9), (15, 40), (30, 200)])
+        );
+        assert_eq!(
+            table.columns::<(u64, u8)>(&["Distance", "Time"]),
+            Ok(vec![(9, 7), (40, 15), (200, 30)])
+        );
+    }
+
+    #[test]
+    fn concatenated_test() {
+        let table = Table::parse(RACES).unwrap();
+
+        assert_eq!(
+            table.concatenated::<(u64, u128)>(&["Time", "Distance"]),
+            Ok((71530, 940200))
+        );
+    }
+
+    #[test]
+    fn error_test() {
+        assert_eq!(
+            Table::parse("Time: 1 2\nDistance: 3"),
+            Err(TableError::RaggedRow {
+                label: "Distance".to_string(),
+                expected: 2,
+                found: 1,
+            })
+        );
+        assert_eq!(
+            Table::parse("Time 1 2"),
+            Err(TableError::MissingLabel("Time 1 2".to_string()))
+        );
+
+        let table = Table::parse("Time: 1 300\nDistance: 3 4").unwrap();
+        assert_eq!(
+            table.columns::<(u32, u32)>(&["Time"]),
+            Err(TableError::WrongRowCount {
+                expected: 2,
+                found: 1,
+            })
+        );
+        assert_eq!(
+            table.columns::<(u8, u8)>(&["Time", "Speed"]),
+            Err(TableError::UnknownRow("Speed".to_string()))
+        );
+        assert_eq!(
+            table.columns::<(u8, u8)>(&["Time", "Distance"]),
+            Err(TableError::InvalidCell {
+                label: "Time".to_string(),
+                cell: "300".to_string(),
+            })
+        );
+        assert_eq!(
+            table.concatenated::<(u8, u8)>(&["Time", "Distance"]),
+            Err(TableError::InvalidCell {
+                label: "Time".to_string(),
+                cell: "1300".to_string(),
+            })
+        );
+    }
+}